@@ -2,12 +2,13 @@ use {
     anyhow::Context,
     clap::Parser,
     indicatif::{MultiProgress, ProgressBar, ProgressStyle},
-    prost::Message,
-    serde::Deserialize,
-    solana_sdk::transaction::{TransactionError, VersionedTransaction},
-    solana_storage_proto::convert::generated,
     tokio::{fs::File, io::BufReader},
-    yellowstone_faithful_car_parser::node::{Node, NodeReader, Nodes},
+    yellowstone_faithful_car_parser::{
+        node::{Node, NodeReader, Nodes, SLOTS_PER_EPOCH},
+        solana::{
+            DecodeError, DecodedRewards, DecodedTransaction, MetaDetector, SignatureVerifier,
+        },
+    },
 };
 
 #[derive(Debug, Parser)]
@@ -24,6 +25,10 @@ struct Args {
     /// Decode Nodes to Solana structs
     #[clap(long)]
     pub decode: bool,
+
+    /// Verify ed25519 signatures of transactions
+    #[clap(long, requires = "decode")]
+    pub verify_signatures: bool,
 }
 
 #[tokio::main]
@@ -51,15 +56,18 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    let mut bar = ReaderProgressBar::new(args.decode);
+    let mut bar = ReaderProgressBar::new(args.decode, args.verify_signatures);
     let mut next_slot = None;
+    let mut verifier = SignatureVerifier::new(None);
     loop {
         let nodes = Nodes::read_until_block(&mut reader).await?;
         if nodes.nodes.is_empty() {
             break;
         }
 
-        for node in nodes.nodes.values() {
+        let mut transactions = vec![];
+
+        for (cid, node) in nodes.nodes.iter() {
             match node {
                 Node::Transaction(frame) => {
                     bar.transaction += 1;
@@ -67,27 +75,34 @@ async fn main() -> anyhow::Result<()> {
                         continue;
                     }
 
-                    let _tx = bincode::deserialize::<VersionedTransaction>(&frame.data.data)
-                        .context("failed to parse tx")?;
-
-                    let buffer = nodes
-                        .reassemble_dataframes(&frame.metadata)
-                        .context("failed to reassemble tx metadata")?;
-                    if buffer.is_empty() {
-                        bar.transaction_meta_empty += 1;
-                    } else {
-                        let buffer = zstd::decode_all(buffer.as_slice())
-                            .context("failed to decompress tx metadata")?;
-                        if decode_protobuf_bincode::<
-                            StoredTransactionStatusMeta,
-                            generated::TransactionStatusMeta,
-                        >("tx metadata", &buffer)
-                        .is_ok()
-                        {
-                            bar.transaction_decode_ok += 1;
-                        } else {
+                    let payload = frame.payload(&nodes).context("failed to reassemble tx")?;
+                    match DecodedTransaction::decode_payload(
+                        &payload,
+                        frame,
+                        &nodes,
+                        &MetaDetector::default(),
+                    ) {
+                        Ok(tx) => {
+                            if tx.meta.is_some() {
+                                bar.transaction_decode_ok += 1;
+                            } else {
+                                bar.transaction_meta_empty += 1;
+                            }
+                            if args.verify_signatures {
+                                transactions.push((*cid, tx.tx));
+                            }
+                        }
+                        Err(DecodeError::AmbiguousMetadata(_) | DecodeError::UnknownMetadata) => {
                             bar.transaction_decode_err += 1;
+                            // signatures do not depend on metadata
+                            if args.verify_signatures {
+                                let tx = bincode::deserialize(&payload)
+                                    .map_err(DecodeError::Transaction)
+                                    .context("failed to decode tx")?;
+                                transactions.push((*cid, tx));
+                            }
                         }
+                        Err(error) => return Err(error).context("failed to decode tx"),
                     }
                 }
                 Node::Entry(_) => bar.entry += 1,
@@ -96,10 +111,22 @@ async fn main() -> anyhow::Result<()> {
 
                     let expected_slot = match next_slot {
                         Some(slot) => slot,
-                        None => frame.slot - frame.slot % 432_000,
+                        None => frame.slot - frame.slot % SLOTS_PER_EPOCH,
                     };
                     next_slot = Some(frame.slot + 1);
                     bar.block_skippped += frame.slot - expected_slot;
+
+                    if args.verify_signatures {
+                        let report = verifier.push_block(frame.slot, &transactions);
+                        bar.signature_ok += (report.signatures - report.failures.len()) as u64;
+                        bar.signature_err += report.failures.len() as u64;
+                        for failure in report.failures {
+                            bar.pb_block.println(format!(
+                                "invalid signature in slot {}, tx {} (signature #{}): {}",
+                                report.slot, failure.cid, failure.index, failure.signature
+                            ));
+                        }
+                    }
                 }
                 Node::Subset(_) => bar.subset += 1,
                 Node::Epoch(_) => bar.epoch += 1,
@@ -109,19 +136,12 @@ async fn main() -> anyhow::Result<()> {
                         continue;
                     }
 
-                    let buffer = nodes
-                        .reassemble_dataframes(&frame.data)
-                        .context("failed to reassemble rewards")?;
-                    let buffer = zstd::decode_all(buffer.as_slice())
-                        .context("failed to decompress rewards")?;
-                    if decode_protobuf_bincode::<Vec<StoredBlockReward>, generated::Rewards>(
-                        "rewards", &buffer,
-                    )
-                    .is_ok()
-                    {
-                        bar.rewards_decode_ok += 1;
-                    } else {
-                        bar.rewards_decode_err += 1;
+                    match DecodedRewards::decode(frame, &nodes) {
+                        Ok(_) => bar.rewards_decode_ok += 1,
+                        Err(DecodeError::AmbiguousRewards | DecodeError::UnknownRewards) => {
+                            bar.rewards_decode_err += 1
+                        }
+                        Err(error) => return Err(error).context("failed to decode rewards"),
                     }
                 }
                 Node::DataFrame(_) => bar.dataframe += 1,
@@ -131,6 +151,14 @@ async fn main() -> anyhow::Result<()> {
         bar.report();
     }
     bar.finish();
+    if args.verify_signatures {
+        println!(
+            "verified {} signatures in {:?} ({:.0} signatures/s)",
+            verifier.signatures,
+            verifier.elapsed,
+            verifier.throughput()
+        );
+    }
 
     Ok(())
 }
@@ -164,10 +192,14 @@ struct ReaderProgressBar {
     pb_rewards_decode_ok: Option<ProgressBar>,
     rewards_decode_err: u64,
     pb_rewards_decode_err: Option<ProgressBar>,
+    signature_ok: u64,
+    pb_signature_ok: Option<ProgressBar>,
+    signature_err: u64,
+    pb_signature_err: Option<ProgressBar>,
 }
 
 impl ReaderProgressBar {
-    fn new(decode: bool) -> Self {
+    fn new(decode: bool, verify_signatures: bool) -> Self {
         let multi = MultiProgress::new();
         Self {
             transaction: 0,
@@ -203,6 +235,12 @@ impl ReaderProgressBar {
             rewards_decode_err: 0,
             pb_rewards_decode_err: decode
                 .then(|| Self::create_pbbar(&multi, "decoded/err", "rewards")),
+            signature_ok: 0,
+            pb_signature_ok: verify_signatures
+                .then(|| Self::create_pbbar(&multi, "verified/ok", "signature")),
+            signature_err: 0,
+            pb_signature_err: verify_signatures
+                .then(|| Self::create_pbbar(&multi, "verified/err", "signature")),
         }
     }
 
@@ -241,6 +279,8 @@ impl ReaderProgressBar {
             ),
             (self.pb_rewards_decode_ok.as_ref(), self.rewards_decode_ok),
             (self.pb_rewards_decode_err.as_ref(), self.rewards_decode_err),
+            (self.pb_signature_ok.as_ref(), self.signature_ok),
+            (self.pb_signature_err.as_ref(), self.signature_err),
         ] {
             if let Some(pb) = pb {
                 pb.set_position(pos);
@@ -265,6 +305,8 @@ impl ReaderProgressBar {
             self.pb_transaction_decode_err.as_ref(),
             self.pb_rewards_decode_ok.as_ref(),
             self.pb_rewards_decode_err.as_ref(),
+            self.pb_signature_ok.as_ref(),
+            self.pb_signature_err.as_ref(),
         ]
         .into_iter()
         .flatten()
@@ -273,37 +315,3 @@ impl ReaderProgressBar {
         }
     }
 }
-
-enum DecodedData<B, P> {
-    Bincode(B),
-    Protobuf(P),
-}
-
-fn decode_protobuf_bincode<B, P>(kind: &str, bytes: &[u8]) -> anyhow::Result<DecodedData<B, P>>
-where
-    B: serde::de::DeserializeOwned,
-    P: Message + Default,
-{
-    match P::decode(bytes) {
-        Ok(value) => Ok(DecodedData::Protobuf(value)),
-        Err(_) => bincode::deserialize::<B>(bytes)
-            .map(DecodedData::Bincode)
-            .with_context(|| format!("failed to decode {kind} with protobuf/bincode")),
-    }
-}
-
-#[allow(dead_code)]
-#[derive(Deserialize)]
-struct StoredTransactionStatusMeta {
-    err: Result<(), TransactionError>,
-    fee: u64,
-    pre_balances: Vec<u64>,
-    post_balances: Vec<u64>,
-}
-
-#[allow(dead_code)]
-#[derive(Deserialize)]
-struct StoredBlockReward {
-    pubkey: String,
-    lamports: i64,
-}
@@ -0,0 +1,259 @@
+use {
+    crate::node::{Block, Entry, Kind, Node, Nodes, Rewards, Transaction},
+    cid::Cid,
+    indexmap::IndexMap,
+    std::collections::HashMap,
+    thiserror::Error,
+};
+
+#[derive(Debug, Clone)]
+pub struct BundleEntry {
+    pub cid: Cid,
+    pub entry: Entry,
+    /// Transactions in `Entry::transactions` order
+    pub transactions: Vec<(Cid, Transaction)>,
+}
+
+/// Block with entries and transactions in the order defined by the block
+#[derive(Debug, Clone)]
+pub struct BlockBundle {
+    pub cid: Cid,
+    pub block: Block,
+    /// Entries in `Block::entries` order
+    pub entries: Vec<BundleEntry>,
+    pub rewards: Option<(Cid, Rewards)>,
+    /// DataFrame nodes, required for reassembly of transactions and rewards data
+    pub dataframes: Nodes,
+    /// Nodes not referenced by the block
+    pub leftovers: IndexMap<Cid, Node>,
+}
+
+impl TryFrom<Nodes> for BlockBundle {
+    type Error = BundleError;
+
+    fn try_from(mut nodes: Nodes) -> Result<Self, Self::Error> {
+        let (cid, block) = match nodes.nodes.pop() {
+            Some((cid, Node::Block(block))) => (cid, block),
+            _ => return Err(BundleError::MissedBlock),
+        };
+        // removal from `IndexMap` with order preserving is O(n), keep positions for leftovers
+        let mut nodes = nodes
+            .nodes
+            .into_iter()
+            .enumerate()
+            .map(|(position, (cid, node))| (cid, (position, node)))
+            .collect::<HashMap<_, _>>();
+        let mut take = |cid: &Cid| nodes.remove(cid).map(|(_position, node)| node);
+
+        let mut entries = Vec::with_capacity(block.entries.len());
+        for entry_cid in block.entries.iter() {
+            let entry = match take(entry_cid) {
+                Some(Node::Entry(entry)) => entry,
+                node => return Err(BundleError::invalid_node(*entry_cid, node, Kind::Entry)),
+            };
+
+            let mut transactions = Vec::with_capacity(entry.transactions.len());
+            for tx_cid in entry.transactions.iter() {
+                match take(tx_cid) {
+                    Some(Node::Transaction(tx)) => transactions.push((*tx_cid, tx)),
+                    node => {
+                        return Err(BundleError::invalid_node(*tx_cid, node, Kind::Transaction));
+                    }
+                }
+            }
+
+            entries.push(BundleEntry {
+                cid: *entry_cid,
+                entry,
+                transactions,
+            });
+        }
+
+        let rewards = match take(&block.rewards) {
+            Some(Node::Rewards(rewards)) => Some((block.rewards, rewards)),
+            None => None,
+            node => {
+                return Err(BundleError::invalid_node(
+                    block.rewards,
+                    node,
+                    Kind::Rewards,
+                ));
+            }
+        };
+
+        let mut nodes = nodes.into_iter().collect::<Vec<_>>();
+        nodes.sort_unstable_by_key(|(_cid, (position, _node))| *position);
+
+        let mut dataframes = Nodes::default();
+        let mut leftovers = IndexMap::new();
+        for (cid, (_position, node)) in nodes {
+            if matches!(node, Node::DataFrame(_)) {
+                dataframes.nodes.insert(cid, node);
+            } else {
+                leftovers.insert(cid, node);
+            }
+        }
+
+        Ok(Self {
+            cid,
+            block,
+            entries,
+            rewards,
+            dataframes,
+            leftovers,
+        })
+    }
+}
+
+impl BlockBundle {
+    pub fn transactions_count(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|entry| entry.transactions.len())
+            .sum()
+    }
+
+    /// Transactions with position in the slot, in the block order
+    pub fn transactions(&self) -> impl Iterator<Item = (usize, &Cid, &Transaction)> {
+        self.entries
+            .iter()
+            .flat_map(|entry| entry.transactions.iter())
+            .enumerate()
+            .map(|(position, (cid, tx))| (position, cid, tx))
+    }
+
+    /// Transaction by position in the slot
+    pub fn transaction(&self, mut position: usize) -> Option<(&Cid, &Transaction)> {
+        for entry in self.entries.iter() {
+            match entry.transactions.get(position) {
+                Some((cid, tx)) => return Some((cid, tx)),
+                None => position -= entry.transactions.len(),
+            }
+        }
+        None
+    }
+
+    /// Position of the transaction in the slot
+    pub fn transaction_position(&self, cid: &Cid) -> Option<usize> {
+        self.transactions()
+            .find_map(|(position, tx_cid, _tx)| (tx_cid == cid).then_some(position))
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BundleError {
+    #[error("Block node is not found")]
+    MissedBlock,
+    #[error("missed cid: {0}")]
+    MissedCid(Cid),
+    #[error("invalid node kind for {cid}: {kind:?} (expected {expected:?})")]
+    InvalidNode {
+        cid: Cid,
+        kind: Kind,
+        expected: Kind,
+    },
+}
+
+impl BundleError {
+    fn invalid_node(cid: Cid, node: Option<Node>, expected: Kind) -> Self {
+        match node {
+            Some(node) => Self::InvalidNode {
+                cid,
+                kind: node.kind(),
+                expected,
+            },
+            None => Self::MissedCid(cid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{BlockBundle, BundleError},
+        crate::{
+            node::{Block, DataFrame, Entry, Node, Nodes, Rewards, Transaction},
+            util::tests::{build_nodes, make_cid},
+        },
+    };
+
+    #[test]
+    fn test_bundle() {
+        let mut nodes = build_nodes([
+            (1, Node::DataFrame(DataFrame::default())),
+            (
+                10,
+                Node::Transaction(Transaction {
+                    index: Some(0),
+                    ..Default::default()
+                }),
+            ),
+            (
+                11,
+                Node::Transaction(Transaction {
+                    index: Some(1),
+                    ..Default::default()
+                }),
+            ),
+            (
+                20,
+                Node::Entry(Entry {
+                    transactions: vec![make_cid(11)],
+                    ..Default::default()
+                }),
+            ),
+            (
+                21,
+                Node::Entry(Entry {
+                    transactions: vec![make_cid(10)],
+                    ..Default::default()
+                }),
+            ),
+            (22, Node::Entry(Entry::default())),
+            (25, Node::Rewards(Rewards::default())),
+            (
+                30,
+                Node::Block(Block {
+                    slot: 1,
+                    entries: vec![make_cid(21), make_cid(20)],
+                    rewards: make_cid(25),
+                    ..Default::default()
+                }),
+            ),
+        ]);
+
+        let bundle = BlockBundle::try_from(nodes.clone()).expect("valid block");
+        assert_eq!(bundle.cid, make_cid(30));
+        assert_eq!(bundle.entries.len(), 2);
+        assert_eq!(bundle.transactions_count(), 2);
+        assert_eq!(
+            bundle
+                .transactions()
+                .map(|(position, cid, _tx)| (position, *cid))
+                .collect::<Vec<_>>(),
+            [(0, make_cid(10)), (1, make_cid(11))]
+        );
+        assert_eq!(
+            bundle.transaction(1).map(|(cid, _tx)| *cid),
+            Some(make_cid(11))
+        );
+        assert_eq!(bundle.transaction(2), None);
+        assert_eq!(bundle.transaction_position(&make_cid(11)), Some(1));
+        assert_eq!(
+            bundle.rewards.map(|(cid, _rewards)| cid),
+            Some(make_cid(25))
+        );
+        assert_eq!(bundle.dataframes.nodes.len(), 1);
+        assert_eq!(bundle.leftovers.keys().collect::<Vec<_>>(), [&make_cid(22)]);
+
+        nodes.nodes.shift_remove(&make_cid(10));
+        assert_eq!(
+            BlockBundle::try_from(nodes).err(),
+            Some(BundleError::MissedCid(make_cid(10)))
+        );
+        assert_eq!(
+            BlockBundle::try_from(Nodes::default()).err(),
+            Some(BundleError::MissedBlock)
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use {
+    crc::{CRC_64_GO_ISO, Crc},
+    fnv::FnvHasher,
+    std::hash::Hasher,
+};
+
+/// Checksum function used to validate reassembled DataFrame data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumKind {
+    /// CRC-64/GO-ISO, used by current archives
+    Crc64,
+    /// FNV-1 64-bit, used by legacy archives
+    Fnv,
+    /// Checksum is not set in the DataFrame
+    None,
+    /// Checksum provided by [`Checksum`] implementation outside of this crate
+    Other(&'static str),
+}
+
+pub trait Checksum {
+    fn kind(&self) -> ChecksumKind;
+
+    fn compute(&self, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Crc64Checksum;
+
+impl Checksum for Crc64Checksum {
+    fn kind(&self) -> ChecksumKind {
+        ChecksumKind::Crc64
+    }
+
+    fn compute(&self, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+        let crc = Crc::<u64>::new(&CRC_64_GO_ISO);
+        let mut digest = crc.digest();
+        for chunk in chunks {
+            digest.update(chunk);
+        }
+        digest.finalize()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FnvChecksum;
+
+impl Checksum for FnvChecksum {
+    fn kind(&self) -> ChecksumKind {
+        ChecksumKind::Fnv
+    }
+
+    fn compute(&self, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+        let mut hasher = FnvHasher::default();
+        for chunk in chunks {
+            hasher.write(chunk);
+        }
+        hasher.finish()
+    }
+}
+
+/// Checksum functions tried in order: current first, then legacy
+pub const DEFAULT_CHECKSUMS: &[&(dyn Checksum + Sync)] = &[&Crc64Checksum, &FnvChecksum];
+
+#[cfg(test)]
+mod tests {
+    use super::{Checksum, Crc64Checksum, FnvChecksum};
+
+    #[test]
+    fn test_checksums() {
+        let data = b"123456789";
+        for checksum in [&Crc64Checksum as &dyn Checksum, &FnvChecksum] {
+            assert_eq!(
+                checksum.compute(&mut [&data[..]].into_iter()),
+                checksum.compute(&mut [&data[..4], &data[4..]].into_iter()),
+            );
+        }
+        // check value of CRC-64/GO-ISO
+        assert_eq!(
+            Crc64Checksum.compute(&mut [&data[..]].into_iter()),
+            0xb90956c775a41001
+        );
+    }
+}
@@ -0,0 +1,473 @@
+use {
+    crate::{
+        node::{
+            Block, CarHeader, Kind, NodeError, NodeReader, NodeWithCid, Nodes, OffsetAndSize,
+            RawNode, ReassableError, Transaction,
+        },
+        rawtx::{RawTransaction, RawTransactionError},
+        types::Signature,
+    },
+    cid::Cid,
+    std::io,
+    thiserror::Error,
+    tokio::io::AsyncRead,
+};
+pub use {
+    cid_to_offset::{CidToOffsetAndSizeBuilder, CidToOffsetAndSizeIndex},
+    compact::{CompactIndex, CompactIndexBuilder, Header, Metadata},
+    lookup::{FoundTransaction, TransactionLookup},
+    sig_to_cid::{SigToCidBuilder, SigToCidIndex},
+    slot_to_cid::{SlotToCidBuilder, SlotToCidIndex},
+};
+
+mod cid_to_offset;
+mod compact;
+mod lookup;
+mod sig_to_cid;
+mod slot_to_cid;
+
+pub const METADATA_KEY_KIND: &[u8] = b"kind";
+pub const METADATA_KEY_EPOCH: &[u8] = b"epoch";
+pub const METADATA_KEY_ROOT_CID: &[u8] = b"rootCid";
+pub const METADATA_KEY_NETWORK: &[u8] = b"network";
+
+#[derive(Debug, Error)]
+pub enum IndexError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Node(#[from] NodeError),
+    #[error(transparent)]
+    InvalidCid(#[from] cid::Error),
+    #[error(transparent)]
+    RawTransaction(#[from] RawTransactionError),
+    #[error(transparent)]
+    Reassable(#[from] ReassableError),
+    #[error("invalid index magic")]
+    InvalidMagic,
+    #[error("unsupported index version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("unexpected end of index data: {0}")]
+    UnexpectedEnd(usize),
+    #[error("invalid hash size: {0}")]
+    InvalidHashSize(u8),
+    #[error("invalid index kind: {kind:?} (expected {expected:?})")]
+    InvalidKind {
+        kind: Option<String>,
+        expected: &'static str,
+    },
+    #[error("metadata key, value or number of entries is longer than 255")]
+    MetadataTooLong,
+    #[error("invalid value size: {size} (expected {expected})")]
+    InvalidValueSize { size: usize, expected: usize },
+    #[error("value size is larger than the index: {0}")]
+    ValueSizeTooLarge(u64),
+    #[error("value is too large: {value} (max {max})")]
+    ValueTooLarge { value: u64, max: u64 },
+    #[error("key is too long: {0}")]
+    KeyTooLong(usize),
+    #[error("too many entries for the index")]
+    TooManyEntries,
+    #[error("failed to find hash domain without collisions for bucket {0}")]
+    HashDomainNotFound(usize),
+    #[error("CAR header does not have roots")]
+    MissedRootCid,
+    #[error("node is not found by the index: {0}")]
+    MissedNode(Cid),
+}
+
+/// Metadata common to Old Faithful indexes of the epoch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexInfo {
+    pub epoch: u64,
+    pub root_cid: Cid,
+    /// `mainnet`, `testnet` or `devnet`
+    pub network: String,
+}
+
+impl IndexInfo {
+    pub fn to_metadata(&self, kind: &str) -> Metadata {
+        let mut metadata = Metadata::default();
+        metadata.insert(METADATA_KEY_KIND, kind);
+        metadata.insert(METADATA_KEY_EPOCH, self.epoch.to_le_bytes());
+        metadata.insert(METADATA_KEY_ROOT_CID, self.root_cid.to_bytes());
+        metadata.insert(METADATA_KEY_NETWORK, self.network.as_str());
+        metadata
+    }
+
+    /// Parse metadata, `None` if any key is missed or invalid
+    pub fn from_metadata(metadata: &Metadata) -> Option<Self> {
+        Some(Self {
+            epoch: u64::from_le_bytes(metadata.get(METADATA_KEY_EPOCH)?.try_into().ok()?),
+            root_cid: Cid::try_from(metadata.get(METADATA_KEY_ROOT_CID)?).ok()?,
+            network: String::from_utf8(metadata.get(METADATA_KEY_NETWORK)?.to_vec()).ok()?,
+        })
+    }
+}
+
+/// Open the index and check the kind in metadata and the value size
+fn open_index<D: AsRef<[u8]>>(
+    data: D,
+    expected: &'static str,
+    value_size: usize,
+) -> Result<CompactIndex<D>, IndexError> {
+    let index = CompactIndex::open(data)?;
+    let header = index.header();
+    let kind = header.metadata.get(METADATA_KEY_KIND);
+    if kind != Some(expected.as_bytes()) {
+        return Err(IndexError::InvalidKind {
+            kind: kind.map(|kind| String::from_utf8_lossy(kind).into_owned()),
+            expected,
+        });
+    }
+    if header.value_size != value_size as u64 {
+        return Err(IndexError::InvalidValueSize {
+            // value size is not larger than the index data
+            size: header.value_size as usize,
+            expected: value_size,
+        });
+    }
+    Ok(index)
+}
+
+/// The first signature of the transaction, frames are used only if the first frame is shorter
+/// than the signature
+fn transaction_signature(
+    transaction: &Transaction,
+    nodes: &Nodes,
+) -> Result<Signature, IndexError> {
+    match RawTransaction::parse_signature(&transaction.data.data) {
+        Err(RawTransactionError::UnexpectedEnd(_)) if !transaction.data.next.is_empty() => {
+            let payload = transaction.payload(nodes)?;
+            Ok(RawTransaction::parse_signature(&payload)?)
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Builders of the CAR indexes, filled in one pass over all nodes
+#[derive(Debug)]
+pub struct CarIndexBuilder {
+    pub slot_to_cid: SlotToCidBuilder,
+    pub cid_to_offset: CidToOffsetAndSizeBuilder,
+    pub sig_to_cid: SigToCidBuilder,
+    /// DataFrames since the last block, required for transactions with a short first frame
+    frames: Nodes,
+    /// Transactions with the first frame shorter than the signature
+    pending: Vec<(Cid, Transaction)>,
+}
+
+impl CarIndexBuilder {
+    pub fn new(info: &IndexInfo) -> Self {
+        Self {
+            slot_to_cid: SlotToCidBuilder::new(info),
+            cid_to_offset: CidToOffsetAndSizeBuilder::new(info),
+            sig_to_cid: SigToCidBuilder::new(info),
+            frames: Nodes::default(),
+            pending: vec![],
+        }
+    }
+
+    /// Read the whole CAR, the root CID is taken from the header
+    pub async fn build<R: AsyncRead + Unpin>(
+        reader: &mut NodeReader<R>,
+        epoch: u64,
+        network: impl Into<String>,
+    ) -> Result<Self, IndexError> {
+        let header = CarHeader::try_from(reader.read_header().await?)?;
+        let info = IndexInfo {
+            epoch,
+            root_cid: *header.roots.first().ok_or(IndexError::MissedRootCid)?,
+            network: network.into(),
+        };
+
+        let mut builder = Self::new(&info);
+        while let Some((node, position)) = reader.read_node_with_offset().await? {
+            builder.push(&node, position)?;
+        }
+        builder.push_pending()?;
+        Ok(builder)
+    }
+
+    /// Transactions with the first frame shorter than the signature are added on the next
+    /// Block node or with [`CarIndexBuilder::push_pending`]
+    pub fn push(&mut self, node: &RawNode, position: OffsetAndSize) -> Result<(), IndexError> {
+        self.cid_to_offset.push(&node.cid, position)?;
+        match node.kind()? {
+            Kind::Block => {
+                let block = Block::try_from(node.get_data())?;
+                self.slot_to_cid.push(block.slot, &node.cid)?;
+                self.push_pending()?;
+            }
+            Kind::Transaction => {
+                let transaction = Transaction::try_from(node.get_data())?;
+                match RawTransaction::parse_signature(&transaction.data.data) {
+                    Ok(signature) => self.sig_to_cid.push(&signature, &node.cid)?,
+                    Err(RawTransactionError::UnexpectedEnd(_))
+                        if !transaction.data.next.is_empty() =>
+                    {
+                        self.pending.push((node.cid, transaction));
+                    }
+                    Err(error) => return Err(error.into()),
+                }
+            }
+            Kind::DataFrame => self.frames.push(NodeWithCid::try_from(node)?),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Add pending transactions, frames are reassembled from DataFrames since the last block
+    pub fn push_pending(&mut self) -> Result<(), IndexError> {
+        for (cid, transaction) in self.pending.drain(..) {
+            let signature = transaction_signature(&transaction, &self.frames)?;
+            self.sig_to_cid.push(&signature, &cid)?;
+        }
+        self.frames = Nodes::default();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            CarIndexBuilder, CidToOffsetAndSizeIndex, CompactIndexBuilder, IndexError, IndexInfo,
+            SigToCidIndex, SlotToCidIndex, TransactionLookup, cid_to_offset,
+        },
+        crate::{
+            node::{Block, Kind, NodeReader, OffsetAndSize},
+            types::Signature,
+            util::tests::make_cid,
+            varint,
+        },
+        cid::Cid,
+        serde_cbor::Value,
+        std::{io::Cursor, path::Path},
+    };
+
+    fn cid_value(cid: Cid) -> Value {
+        Value::Tag(
+            42,
+            Box::new(Value::Bytes([&[0], &cid.to_bytes()[..]].concat())),
+        )
+    }
+
+    fn dataframe_value(index: u64, total: u64, data: &[u8], next: &[Cid]) -> Value {
+        Value::Array(vec![
+            Value::Integer(6),
+            Value::Null,
+            Value::Integer(index.into()),
+            Value::Integer(total.into()),
+            Value::Bytes(data.to_vec()),
+            Value::Array(next.iter().copied().map(cid_value).collect()),
+        ])
+    }
+
+    /// CAR with nodes, CID of the node is `make_cid(position + 1)`
+    fn build_car(nodes: &[Value]) -> (Vec<u8>, Vec<(Cid, OffsetAndSize)>) {
+        let header = serde_cbor::to_vec(&Value::Map(
+            [
+                (
+                    Value::Text("roots".to_owned()),
+                    Value::Array(vec![cid_value(make_cid(0))]),
+                ),
+                (Value::Text("version".to_owned()), Value::Integer(1)),
+            ]
+            .into_iter()
+            .collect(),
+        ))
+        .expect("valid header");
+        let mut car = vec![];
+        varint::encode_varint(header.len() as u64, &mut car);
+        car.extend_from_slice(&header);
+
+        let mut sections = vec![];
+        for (i, node) in nodes.iter().enumerate() {
+            let data = serde_cbor::to_vec(node).expect("valid node");
+            let cid = make_cid(i as u8 + 1);
+            let offset = car.len() as u64;
+            let cid_bytes = cid.to_bytes();
+            varint::encode_varint((cid_bytes.len() + data.len()) as u64, &mut car);
+            car.extend_from_slice(&cid_bytes);
+            car.extend_from_slice(&data);
+            let size = car.len() as u64 - offset;
+            sections.push((cid, OffsetAndSize { offset, size }));
+        }
+        (car, sections)
+    }
+
+    async fn build_indexes(car: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let mut reader = NodeReader::new(car);
+        let mut builder = CarIndexBuilder::build(&mut reader, 0, "mainnet")
+            .await
+            .expect("valid car");
+        let mut indexes = (vec![], vec![], vec![]);
+        builder
+            .slot_to_cid
+            .write(&mut indexes.0)
+            .await
+            .expect("written");
+        builder
+            .cid_to_offset
+            .write(&mut indexes.1)
+            .await
+            .expect("written");
+        builder
+            .sig_to_cid
+            .write(&mut indexes.2)
+            .await
+            .expect("written");
+        indexes
+    }
+
+    #[tokio::test]
+    async fn test_build_indexes() {
+        let slots = [10, 11, 13];
+        let blocks = slots.map(|slot| Value::Array(vec![Value::Integer(2), Value::Integer(slot)]));
+        let (car, sections) = build_car(&blocks);
+        let (slot_to_cid, cid_to_offset, _) = build_indexes(&car).await;
+        let slot_to_cid = SlotToCidIndex::open(slot_to_cid).expect("valid index");
+        let cid_to_offset = CidToOffsetAndSizeIndex::open(cid_to_offset).expect("valid index");
+        assert_eq!(
+            slot_to_cid.info(),
+            Some(IndexInfo {
+                epoch: 0,
+                root_cid: make_cid(0),
+                network: "mainnet".to_owned(),
+            })
+        );
+
+        let mut reader = NodeReader::new(Cursor::new(car));
+        for (slot, (cid, position)) in slots.into_iter().zip(sections) {
+            assert_eq!(
+                slot_to_cid.get(slot as u64).expect("valid index"),
+                Some(cid)
+            );
+            let found = cid_to_offset.get(&cid).expect("valid index");
+            assert_eq!(found, Some(position));
+
+            let node = reader
+                .read_node_at(position.offset)
+                .await
+                .expect("valid car")
+                .expect("node exists");
+            assert_eq!(node.cid, cid);
+            let block = Block::try_from(node.get_data()).expect("valid block");
+            assert_eq!(block.slot, slot as u64);
+        }
+        assert_eq!(slot_to_cid.get(12).expect("valid index"), None);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_value_size() {
+        let info = IndexInfo {
+            epoch: 0,
+            root_cid: make_cid(0),
+            network: "mainnet".to_owned(),
+        };
+        let mut builder = CompactIndexBuilder::new(36, info.to_metadata(cid_to_offset::KIND));
+        builder.push(b"key", &[0; 36]).expect("valid value");
+        let mut data = vec![];
+        builder.write(&mut data).await.expect("written");
+        assert!(matches!(
+            CidToOffsetAndSizeIndex::open(data),
+            Err(IndexError::InvalidValueSize {
+                size: 36,
+                expected: 9
+            })
+        ));
+    }
+
+    /// CAR and the `slot-to-cid` index generated for it by the Go tool are not in the repository,
+    /// see `tests/fixtures/README.md`
+    #[tokio::test]
+    #[ignore = "requires the fixture generated by the Go tool"]
+    async fn test_go_slot_to_cid() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let car = std::fs::read(dir.join("epoch.car")).expect("fixture CAR");
+        let expected = std::fs::read(dir.join("slot-to-cid.index")).expect("fixture index");
+
+        let index = SlotToCidIndex::open(expected.as_slice()).expect("valid index");
+        let info = index.info().expect("valid metadata");
+        let mut reader = NodeReader::new(car.as_slice());
+        let mut blocks = 0;
+        while let Some(node) = reader.read_node().await.expect("valid car") {
+            if node.kind().expect("valid node") == Kind::Block {
+                let block = Block::try_from(node.get_data()).expect("valid block");
+                assert_eq!(index.get(block.slot).expect("valid index"), Some(node.cid));
+                blocks += 1;
+            }
+        }
+        assert!(blocks > 0);
+
+        let mut reader = NodeReader::new(car.as_slice());
+        let mut builder = CarIndexBuilder::build(&mut reader, info.epoch, info.network)
+            .await
+            .expect("valid car");
+        let mut data = vec![];
+        builder.slot_to_cid.write(&mut data).await.expect("written");
+        assert_eq!(data, expected);
+    }
+
+    #[tokio::test]
+    async fn test_find_transaction() {
+        // the first frame is longer and shorter than the signature
+        for split in [100, 30] {
+            find_transaction(split).await;
+        }
+    }
+
+    async fn find_transaction(split: usize) {
+        // legacy transaction: one signature, one account key, no instructions
+        let signature = Signature::from([7; 64]);
+        let mut payload = vec![1];
+        payload.extend_from_slice(signature.as_ref());
+        payload.extend_from_slice(&[1, 0, 0, 1]);
+        payload.extend_from_slice(&[2; 32]);
+        payload.extend_from_slice(&[3; 32]);
+        payload.push(0);
+
+        let (car, sections) = build_car(&[
+            dataframe_value(1, 2, &payload[split..], &[]),
+            Value::Array(vec![
+                Value::Integer(0),
+                dataframe_value(0, 2, &payload[..split], &[make_cid(1)]),
+                dataframe_value(0, 1, &[], &[]),
+                Value::Integer(10),
+                Value::Integer(0),
+            ]),
+            Value::Array(vec![Value::Integer(2), Value::Integer(10)]),
+        ]);
+        let (_, cid_to_offset, sig_to_cid) = build_indexes(&car).await;
+        let mut lookup = TransactionLookup::new(
+            SigToCidIndex::open(sig_to_cid).expect("valid index"),
+            CidToOffsetAndSizeIndex::open(cid_to_offset).expect("valid index"),
+            NodeReader::new(Cursor::new(car)),
+        );
+
+        let found = lookup
+            .find(&signature)
+            .await
+            .expect("valid car")
+            .expect("transaction exists");
+        assert_eq!((found.cid, found.position), sections[1]);
+        assert_eq!(found.slot(), 10);
+        assert_eq!(
+            found
+                .transaction
+                .payload(&found.nodes)
+                .expect("reassembled"),
+            payload
+        );
+        #[cfg(feature = "solana")]
+        {
+            let decoded = found.decode().expect("valid transaction");
+            assert_eq!(decoded.signature, signature);
+            assert_eq!(decoded.meta, None);
+        }
+
+        let missed = lookup.find(&Signature::from([8; 64])).await;
+        assert!(matches!(missed, Ok(None)));
+    }
+}
@@ -0,0 +1,87 @@
+use {
+    crate::{
+        index::{CompactIndex, CompactIndexBuilder, IndexError, IndexInfo, open_index},
+        node::OffsetAndSize,
+    },
+    cid::Cid,
+    tokio::io::AsyncWrite,
+};
+
+pub const KIND: &str = "cid-to-offset-and-size";
+/// Offset as u48 little-endian and size as u24 little-endian
+pub const VALUE_SIZE: usize = 6 + 3;
+
+const MAX_OFFSET: u64 = (1 << 48) - 1;
+const MAX_SIZE: u64 = (1 << 24) - 1;
+
+/// Builder of the `cid-to-offset-and-size` index, key is the CID bytes, value is the position
+/// of the node section in the CAR file
+#[derive(Debug)]
+pub struct CidToOffsetAndSizeBuilder {
+    index: CompactIndexBuilder,
+}
+
+impl CidToOffsetAndSizeBuilder {
+    pub fn new(info: &IndexInfo) -> Self {
+        Self {
+            index: CompactIndexBuilder::new(VALUE_SIZE, info.to_metadata(KIND)),
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn push(&mut self, cid: &Cid, position: OffsetAndSize) -> Result<(), IndexError> {
+        for (value, max) in [(position.offset, MAX_OFFSET), (position.size, MAX_SIZE)] {
+            if value > max {
+                return Err(IndexError::ValueTooLarge { value, max });
+            }
+        }
+
+        let mut value = [0; VALUE_SIZE];
+        value[..6].copy_from_slice(&position.offset.to_le_bytes()[..6]);
+        value[6..].copy_from_slice(&position.size.to_le_bytes()[..3]);
+        self.index.push(&cid.to_bytes(), &value)
+    }
+
+    pub async fn write<W: AsyncWrite + Unpin>(
+        &mut self,
+        writer: &mut W,
+    ) -> Result<u64, IndexError> {
+        self.index.write(writer).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CidToOffsetAndSizeIndex<D> {
+    index: CompactIndex<D>,
+}
+
+impl<D: AsRef<[u8]>> CidToOffsetAndSizeIndex<D> {
+    pub fn open(data: D) -> Result<Self, IndexError> {
+        open_index(data, KIND, VALUE_SIZE).map(|index| Self { index })
+    }
+
+    pub fn info(&self) -> Option<IndexInfo> {
+        IndexInfo::from_metadata(&self.index.header().metadata)
+    }
+
+    /// Position of the node section, use with [`crate::node::NodeReader::read_node_at`]
+    pub fn get(&self, cid: &Cid) -> Result<Option<OffsetAndSize>, IndexError> {
+        Ok(self.index.lookup(&cid.to_bytes())?.map(|value| {
+            let mut offset = [0; 8];
+            offset[..6].copy_from_slice(&value[..6]);
+            let mut size = [0; 8];
+            size[..3].copy_from_slice(&value[6..]);
+            OffsetAndSize {
+                offset: u64::from_le_bytes(offset),
+                size: u64::from_le_bytes(size),
+            }
+        }))
+    }
+}
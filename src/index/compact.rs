@@ -0,0 +1,695 @@
+use {
+    crate::index::IndexError,
+    std::{
+        fs::File,
+        io::{self, BufReader, BufWriter, Read, Seek, Write},
+        ops::Range,
+        path::PathBuf,
+    },
+    tokio::io::{AsyncWrite, AsyncWriteExt},
+    xxhash_rust::xxh64::{Xxh64, xxh64},
+};
+
+// Layout of `compactindexsized` from Old Faithful:
+//
+// magic "compiszd" | header size u32 | value size u64 | buckets u32 | version u8 | metadata
+// bucket headers: domain u32 | entries u32 | hash size u8 | padding u8 | offset u48
+// buckets: entries of `hash | value` sorted by hash in Eytzinger order
+pub const MAGIC: [u8; 8] = *b"compiszd";
+pub const VERSION: u8 = 1;
+
+const BUCKET_HEADER_SIZE: usize = 16;
+const HASH_SIZE: u8 = 3;
+const TARGET_ENTRIES_PER_BUCKET: usize = 10_000;
+const MAX_HASH_DOMAIN: u32 = 1000;
+const DEFAULT_MAX_MEMORY: usize = 256 << 20; // 256MiB
+
+/// Key-value pairs stored in the index header, keys and values are up to 255 bytes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Metadata {
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    pub fn insert(&mut self, key: impl Into<Vec<u8>>, value: impl Into<Vec<u8>>) {
+        let (key, value) = (key.into(), value.into());
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.entries.push((key, value)),
+        }
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), IndexError> {
+        let len = |len: usize| u8::try_from(len).map_err(|_| IndexError::MetadataTooLong);
+        buf.push(len(self.entries.len())?);
+        for (key, value) in &self.entries {
+            buf.push(len(key.len())?);
+            buf.extend_from_slice(key);
+            buf.push(len(value.len())?);
+            buf.extend_from_slice(value);
+        }
+        Ok(())
+    }
+
+    fn decode(mut data: &[u8], offset: usize) -> Result<Self, IndexError> {
+        let total = data.len();
+        let mut read = |len: usize| match data.split_at_checked(len) {
+            Some((value, rest)) => {
+                data = rest;
+                Ok(value)
+            }
+            None => Err(IndexError::UnexpectedEnd(offset + total)),
+        };
+
+        let mut metadata = Self::default();
+        for _ in 0..read(1)?[0] {
+            let key = read(1)?[0] as usize;
+            let key = read(key)?.to_vec();
+            let value = read(1)?[0] as usize;
+            let value = read(value)?.to_vec();
+            metadata.entries.push((key, value));
+        }
+        Ok(metadata)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub value_size: u64,
+    pub num_buckets: u32,
+    pub metadata: Metadata,
+}
+
+impl Header {
+    fn encode(&self) -> Result<Vec<u8>, IndexError> {
+        let mut metadata = vec![];
+        self.metadata.encode(&mut metadata)?;
+
+        let mut buf = Vec::with_capacity(MAGIC.len() + 17 + metadata.len());
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&((8 + 4 + 1 + metadata.len()) as u32).to_le_bytes());
+        buf.extend_from_slice(&self.value_size.to_le_bytes());
+        buf.extend_from_slice(&self.num_buckets.to_le_bytes());
+        buf.push(VERSION);
+        buf.extend_from_slice(&metadata);
+        Ok(buf)
+    }
+
+    /// Decode header, returns header and offset of bucket headers
+    fn decode(data: &[u8]) -> Result<(Self, usize), IndexError> {
+        let prefix = MAGIC.len() + 4;
+        if data.len() < prefix + 13 {
+            return Err(IndexError::UnexpectedEnd(data.len()));
+        }
+        if data[..MAGIC.len()] != MAGIC {
+            return Err(IndexError::InvalidMagic);
+        }
+        let size = read_uint(&data[MAGIC.len()..prefix]) as usize;
+        let Some(header) = data.get(prefix..prefix + size).filter(|_| size >= 13) else {
+            return Err(IndexError::UnexpectedEnd(prefix + size));
+        };
+        if header[12] != VERSION {
+            return Err(IndexError::UnsupportedVersion(header[12]));
+        }
+
+        let header = Self {
+            value_size: read_uint(&header[0..8]),
+            num_buckets: read_uint(&header[8..12]) as u32,
+            metadata: Metadata::decode(&header[13..], prefix + 13)?,
+        };
+        Ok((header, prefix + size))
+    }
+}
+
+/// Read little-endian unsigned integer up to 8 bytes
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .rev()
+        .fold(0, |value, byte| (value << 8) | *byte as u64)
+}
+
+/// Bucket of the key, uniform over `num_buckets`
+pub fn bucket_hash(key: &[u8], num_buckets: u32) -> usize {
+    bucket_of_key_hash(xxh64(key, 0), num_buckets)
+}
+
+const fn bucket_of_key_hash(mut u: u64, num_buckets: u32) -> usize {
+    let n = num_buckets as u64;
+    let r = n.wrapping_neg() % n;
+    while u < r {
+        u = mix_u64(u);
+    }
+    (u % n) as usize
+}
+
+/// Finalizer of MurmurHash3
+const fn mix_u64(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51afd7ed558ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ceb9fe1a85ec53);
+    x ^= x >> 33;
+    x
+}
+
+/// Hash of the key within the bucket, the domain is mixed in as a 32 bytes prefix block
+pub fn entry_hash(domain: u32, key: &[u8]) -> u64 {
+    let mut block = [0; 32];
+    block[..4].copy_from_slice(&domain.to_le_bytes());
+    let mut hasher = Xxh64::new(0);
+    hasher.update(&block);
+    hasher.update(key);
+    hasher.digest()
+}
+
+const fn hash_mask(hash_size: u8) -> u64 {
+    match hash_size {
+        8.. => u64::MAX,
+        size => (1 << (8 * size as u32)) - 1,
+    }
+}
+
+/// Bucket entries with the hash domain, entries are in Eytzinger order
+#[derive(Debug)]
+struct MinedBucket<'a> {
+    domain: u32,
+    entries: Vec<(u64, &'a [u8])>,
+}
+
+/// Builder of the index with fixed size values
+///
+/// Keys can have any length, if a key is pushed more than once the first value is kept.
+///
+/// Pushed entries are buffered in memory up to `max_memory` bytes, then spilled to a temporary
+/// file. On write spilled entries are split by ranges of buckets to temporary files, every range
+/// fits `max_memory`, so memory usage does not depend on the number of entries.
+#[derive(Debug)]
+pub struct CompactIndexBuilder {
+    value_size: usize,
+    pub metadata: Metadata,
+    max_memory: usize,
+    temp_dir: Option<PathBuf>,
+    len: usize,
+    /// Entries not spilled yet: `key size u32 | key | value`
+    buffer: Vec<u8>,
+    spill: Option<BufWriter<File>>,
+}
+
+impl CompactIndexBuilder {
+    pub const fn new(value_size: usize, metadata: Metadata) -> Self {
+        Self {
+            value_size,
+            metadata,
+            max_memory: DEFAULT_MAX_MEMORY,
+            temp_dir: None,
+            len: 0,
+            buffer: vec![],
+            spill: None,
+        }
+    }
+
+    /// Max size of buffered entries, 256MiB by default
+    pub const fn with_max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = max_memory;
+        self
+    }
+
+    /// Directory for temporary files, by default [`std::env::temp_dir`]
+    pub fn with_temp_dir(mut self, temp_dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = Some(temp_dir.into());
+        self
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, key: &[u8], value: &[u8]) -> Result<(), IndexError> {
+        if value.len() != self.value_size {
+            return Err(IndexError::InvalidValueSize {
+                size: value.len(),
+                expected: self.value_size,
+            });
+        }
+        let key_size = u32::try_from(key.len()).map_err(|_| IndexError::KeyTooLong(key.len()))?;
+
+        self.buffer.extend_from_slice(&key_size.to_le_bytes());
+        self.buffer.extend_from_slice(key);
+        self.buffer.extend_from_slice(value);
+        self.len += 1;
+        if self.buffer.len() >= self.max_memory {
+            self.spill_buffer()?;
+        }
+        Ok(())
+    }
+
+    fn temp_file(&self) -> io::Result<File> {
+        match &self.temp_dir {
+            Some(dir) => tempfile::tempfile_in(dir),
+            None => tempfile::tempfile(),
+        }
+    }
+
+    fn spill_buffer(&mut self) -> io::Result<()> {
+        if self.spill.is_none() {
+            self.spill = Some(BufWriter::new(self.temp_file()?));
+        }
+        let spill = self.spill.as_mut().expect("created spill");
+        spill.write_all(&self.buffer)?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Iterate over `(key, value)` entries of the buffer
+    fn entries<'a>(&self, mut data: &'a [u8]) -> impl Iterator<Item = (&'a [u8], &'a [u8])> {
+        let value_size = self.value_size;
+        std::iter::from_fn(move || {
+            let (key_size, rest) = data.split_first_chunk::<4>()?;
+            let (key, rest) = rest.split_at(u32::from_le_bytes(*key_size) as usize);
+            let (value, rest) = rest.split_at(value_size);
+            data = rest;
+            Some((key, value))
+        })
+    }
+
+    /// Split spilled entries to temporary files by ranges of buckets
+    fn split_spill(&mut self, num_buckets: usize) -> Result<Vec<(File, Range<usize>)>, IndexError> {
+        self.spill_buffer()?;
+        let spill = self.spill.as_mut().expect("created spill");
+        spill.flush()?;
+        let mut spill = spill.get_ref().try_clone()?;
+        spill.rewind()?;
+        let spill_size = spill.metadata()?.len() as usize;
+
+        let parts = spill_size
+            .div_ceil(self.max_memory.max(1))
+            .clamp(1, num_buckets);
+        let mut files = (0..parts)
+            .map(|_| self.temp_file().map(BufWriter::new))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut reader = BufReader::new(spill);
+        let mut entry = vec![];
+        let mut key_size = [0; 4];
+        loop {
+            match reader.read_exact(&mut key_size) {
+                Ok(()) => {}
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(error) => return Err(error.into()),
+            }
+            entry.resize(
+                4 + u32::from_le_bytes(key_size) as usize + self.value_size,
+                0,
+            );
+            entry[..4].copy_from_slice(&key_size);
+            reader.read_exact(&mut entry[4..])?;
+
+            let key = &entry[4..entry.len() - self.value_size];
+            let part = bucket_hash(key, num_buckets as u32) * parts / num_buckets;
+            files[part].write_all(&entry)?;
+        }
+
+        files
+            .into_iter()
+            .enumerate()
+            .map(|(part, file)| {
+                let mut file = file.into_inner().map_err(|error| error.into_error())?;
+                file.rewind()?;
+                let range = part * num_buckets / parts..(part + 1) * num_buckets / parts;
+                Ok((file, range))
+            })
+            .collect()
+    }
+
+    /// Find the first hash domain without collisions of truncated hashes in the bucket
+    fn mine_bucket<'a>(
+        bucket: usize,
+        mut entries: Vec<(&[u8], &'a [u8])>,
+    ) -> Result<MinedBucket<'a>, IndexError> {
+        entries.sort_by_key(|(key, _value)| *key);
+        entries.dedup_by_key(|(key, _value)| *key);
+
+        let mask = hash_mask(HASH_SIZE);
+        for domain in 0..MAX_HASH_DOMAIN {
+            let mut hashes = entries
+                .iter()
+                .map(|(key, value)| (entry_hash(domain, key) & mask, *value))
+                .collect::<Vec<_>>();
+            hashes.sort_by_key(|(hash, _value)| *hash);
+            if hashes.windows(2).all(|pair| pair[0].0 != pair[1].0) {
+                return Ok(MinedBucket {
+                    domain,
+                    entries: eytzinger(&hashes),
+                });
+            }
+        }
+        Err(IndexError::HashDomainNotFound(bucket))
+    }
+
+    /// Mine buckets of the range, bucket headers are pushed to `headers` and bucket entries are
+    /// written to `output`
+    fn mine_buckets(
+        &self,
+        data: &[u8],
+        buckets: Range<usize>,
+        num_buckets: usize,
+        headers: &mut Vec<(u32, u32)>,
+        output: &mut impl Write,
+    ) -> Result<(), IndexError> {
+        let mut entries = vec![vec![]; buckets.len()];
+        for (key, value) in self.entries(data) {
+            let bucket = bucket_hash(key, num_buckets as u32);
+            entries[bucket - buckets.start].push((key, value));
+        }
+
+        for (bucket, entries) in buckets.zip(entries) {
+            let MinedBucket { domain, entries } = Self::mine_bucket(bucket, entries)?;
+            headers.push((domain, entries.len() as u32));
+            for (hash, value) in entries {
+                output.write_all(&hash.to_le_bytes()[..HASH_SIZE as usize])?;
+                output.write_all(value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the index, returns the number of written bytes
+    ///
+    /// Blocking file IO is used for temporary files if entries were spilled.
+    pub async fn write<W: AsyncWrite + Unpin>(
+        &mut self,
+        writer: &mut W,
+    ) -> Result<u64, IndexError> {
+        let num_buckets = self.len.div_ceil(TARGET_ENTRIES_PER_BUCKET).max(1);
+        let header = Header {
+            value_size: self.value_size as u64,
+            num_buckets: u32::try_from(num_buckets).map_err(|_| IndexError::TooManyEntries)?,
+            metadata: self.metadata.clone(),
+        }
+        .encode()?;
+
+        let mut headers = Vec::with_capacity(num_buckets);
+        let mut mined = match self.spill {
+            None => {
+                let mut mined = vec![];
+                self.mine_buckets(
+                    &self.buffer,
+                    0..num_buckets,
+                    num_buckets,
+                    &mut headers,
+                    &mut mined,
+                )?;
+                Mined::Memory(mined)
+            }
+            Some(_) => {
+                let mut mined = BufWriter::new(self.temp_file()?);
+                let mut data = vec![];
+                for (mut file, buckets) in self.split_spill(num_buckets)? {
+                    data.clear();
+                    file.read_to_end(&mut data)?;
+                    self.mine_buckets(&data, buckets, num_buckets, &mut headers, &mut mined)?;
+                }
+                let mut mined = mined.into_inner().map_err(|error| error.into_error())?;
+                mined.rewind()?;
+                Mined::File(mined)
+            }
+        };
+
+        writer.write_all(&header).await?;
+        let entry_size = HASH_SIZE as usize + self.value_size;
+        let mut offset = (header.len() + num_buckets * BUCKET_HEADER_SIZE) as u64;
+        for (domain, entries) in headers {
+            let mut buf = [0; BUCKET_HEADER_SIZE];
+            buf[0..4].copy_from_slice(&domain.to_le_bytes());
+            buf[4..8].copy_from_slice(&entries.to_le_bytes());
+            buf[8] = HASH_SIZE;
+            buf[10..16].copy_from_slice(&offset.to_le_bytes()[..6]);
+            writer.write_all(&buf).await?;
+            offset += entries as u64 * entry_size as u64;
+        }
+
+        match &mut mined {
+            Mined::Memory(mined) => writer.write_all(mined).await?,
+            Mined::File(file) => {
+                let mut buf = vec![0; 1 << 20];
+                loop {
+                    let size = file.read(&mut buf)?;
+                    if size == 0 {
+                        break;
+                    }
+                    writer.write_all(&buf[..size]).await?;
+                }
+            }
+        }
+        writer.flush().await?;
+
+        Ok(offset)
+    }
+}
+
+/// Bucket entries in the write order
+#[derive(Debug)]
+enum Mined {
+    Memory(Vec<u8>),
+    File(File),
+}
+
+/// Reorder sorted items to the Eytzinger layout (BFS order of the binary search tree)
+fn eytzinger<T: Copy>(sorted: &[T]) -> Vec<T> {
+    fn fill<T: Copy>(sorted: &[T], out: &mut [T], i: &mut usize, k: usize) {
+        if k <= sorted.len() {
+            fill(sorted, out, i, 2 * k);
+            out[k - 1] = sorted[*i];
+            *i += 1;
+            fill(sorted, out, i, 2 * k + 1);
+        }
+    }
+
+    let mut out = sorted.to_vec();
+    fill(sorted, &mut out, &mut 0, 1);
+    out
+}
+
+/// Reader of the index over any bytes, e.g. file content or memory map
+#[derive(Debug, Clone)]
+pub struct CompactIndex<D> {
+    data: D,
+    header: Header,
+    buckets_offset: usize,
+}
+
+impl<D: AsRef<[u8]>> CompactIndex<D> {
+    pub fn open(data: D) -> Result<Self, IndexError> {
+        let (header, buckets_offset) = Header::decode(data.as_ref())?;
+        if header.num_buckets == 0 {
+            return Err(IndexError::UnexpectedEnd(buckets_offset));
+        }
+        let end = buckets_offset + header.num_buckets as usize * BUCKET_HEADER_SIZE;
+        if data.as_ref().len() < end {
+            return Err(IndexError::UnexpectedEnd(end));
+        }
+        // an entry with a larger value does not fit, entry size does not overflow in lookups
+        if header.value_size > data.as_ref().len() as u64 {
+            return Err(IndexError::ValueSizeTooLarge(header.value_size));
+        }
+
+        Ok(Self {
+            data,
+            header,
+            buckets_offset,
+        })
+    }
+
+    pub const fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Find value of the key
+    ///
+    /// Only truncated hashes of keys are stored, so a key which was not pushed to the builder can
+    /// return a value of another key.
+    pub fn lookup(&self, key: &[u8]) -> Result<Option<&[u8]>, IndexError> {
+        let data = self.data.as_ref();
+        let bucket = bucket_hash(key, self.header.num_buckets);
+        let offset = self.buckets_offset + bucket * BUCKET_HEADER_SIZE;
+        let bucket = &data[offset..offset + BUCKET_HEADER_SIZE];
+        let domain = read_uint(&bucket[0..4]) as u32;
+        let num_entries = read_uint(&bucket[4..8]) as usize;
+        let hash_size = bucket[8];
+        if !(1..=8).contains(&hash_size) {
+            return Err(IndexError::InvalidHashSize(hash_size));
+        }
+        let offset = read_uint(&bucket[10..16]) as usize;
+
+        let entry_size = hash_size as usize + self.header.value_size as usize;
+        let end = num_entries
+            .checked_mul(entry_size)
+            .and_then(|size| size.checked_add(offset))
+            .unwrap_or(usize::MAX);
+        let Some(entries) = data.get(offset..end) else {
+            return Err(IndexError::UnexpectedEnd(end));
+        };
+
+        let target = entry_hash(domain, key) & hash_mask(hash_size);
+        let mut index = 0;
+        while index < num_entries {
+            let entry = &entries[index * entry_size..(index + 1) * entry_size];
+            let (hash, value) = entry.split_at(hash_size as usize);
+            let hash = read_uint(hash);
+            if hash == target {
+                return Ok(Some(value));
+            }
+            index = (index << 1) | 1;
+            if hash < target {
+                index += 1;
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{CompactIndex, CompactIndexBuilder, Metadata, bucket_hash, entry_hash, eytzinger},
+        crate::index::IndexError,
+        xxhash_rust::xxh64::xxh64,
+    };
+
+    #[test]
+    fn test_hashes() {
+        let mut block = [0; 35];
+        assert_eq!(entry_hash(0, b""), xxh64(&block[..32], 0));
+        block[0] = 5;
+        block[32..].copy_from_slice(b"key");
+        assert_eq!(entry_hash(5, b"key"), xxh64(&block, 0));
+        assert_eq!(bucket_hash(b"key", 1), 0);
+        assert!(bucket_hash(b"key", 7) < 7);
+        assert_eq!(eytzinger(&[1, 2, 3, 4, 5, 6]), [4, 2, 6, 1, 3, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_layout() {
+        let mut metadata = Metadata::default();
+        metadata.insert("kind", "test");
+        let mut builder = CompactIndexBuilder::new(2, metadata);
+        builder.push(b"a", &[1, 2]).expect("valid value");
+        builder.push(b"b", &[3, 4]).expect("valid value");
+        let mut data = vec![];
+        builder.write(&mut data).await.expect("written");
+
+        // entry hashes with domain 0, entries are sorted by hash in Eytzinger order
+        let hash = |key: &[u8]| xxh64(&[&[0; 32], key].concat(), 0) & 0xff_ffff;
+        let (hash_a, hash_b) = (hash(b"a"), hash(b"b"));
+        assert_ne!(hash_a, hash_b);
+        let mut entries = [(hash_a, [1, 2]), (hash_b, [3, 4])];
+        entries.sort_by_key(|(hash, _value)| std::cmp::Reverse(*hash));
+
+        let mut expected = b"compiszd".to_vec();
+        expected.extend_from_slice(&24u32.to_le_bytes());
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        expected.extend_from_slice(&1u32.to_le_bytes());
+        expected.push(1);
+        expected.extend_from_slice(b"\x01\x04kind\x04test");
+        // bucket header: domain, entries, hash size, padding, offset
+        expected.extend_from_slice(&0u32.to_le_bytes());
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(&[3, 0]);
+        expected.extend_from_slice(&52u64.to_le_bytes()[..6]);
+        for (hash, value) in entries {
+            expected.extend_from_slice(&hash.to_le_bytes()[..3]);
+            expected.extend_from_slice(&value);
+        }
+        assert_eq!(data, expected);
+    }
+
+    #[tokio::test]
+    async fn test_spill() {
+        let build = |builder: CompactIndexBuilder| async move {
+            let mut builder = builder;
+            for key in 0u32..25_000 {
+                builder
+                    .push(&key.to_le_bytes(), &(key * 2).to_le_bytes())
+                    .expect("valid value");
+            }
+            let mut data = vec![];
+            builder.write(&mut data).await.expect("written");
+            data
+        };
+
+        let data = build(CompactIndexBuilder::new(4, Metadata::default())).await;
+        for max_memory in [1, 1000, 100_000] {
+            let builder = CompactIndexBuilder::new(4, Metadata::default())
+                .with_max_memory(max_memory)
+                .with_temp_dir(std::env::temp_dir());
+            assert_eq!(build(builder).await, data, "max memory {max_memory}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_lookup() {
+        let mut metadata = Metadata::default();
+        metadata.insert("kind", "test");
+        let mut builder = CompactIndexBuilder::new(4, metadata.clone());
+        for key in 0u32..25_000 {
+            builder
+                .push(&key.to_le_bytes(), &(key * 2).to_le_bytes())
+                .expect("valid value");
+        }
+        // duplicate key, first value is kept
+        builder
+            .push(&0u32.to_le_bytes(), &7u32.to_le_bytes())
+            .expect("valid value");
+        assert!(matches!(
+            builder.push(b"key", b"value"),
+            Err(IndexError::InvalidValueSize {
+                size: 5,
+                expected: 4
+            })
+        ));
+
+        let mut data = vec![];
+        let size = builder.write(&mut data).await.expect("written");
+        assert_eq!(size, data.len() as u64);
+        assert_eq!(&data[..8], b"compiszd");
+
+        let index = CompactIndex::open(data.as_slice()).expect("valid index");
+        assert_eq!(index.header().num_buckets, 3);
+        assert_eq!(index.header().value_size, 4);
+        assert_eq!(index.header().metadata, metadata);
+        for key in 0u32..25_000 {
+            assert_eq!(
+                index.lookup(&key.to_le_bytes()).expect("valid index"),
+                Some((key * 2).to_le_bytes().as_slice())
+            );
+        }
+
+        assert!(matches!(
+            CompactIndex::open(&data[..40]),
+            Err(IndexError::UnexpectedEnd(_))
+        ));
+        let mut invalid = data.clone();
+        invalid[12..20].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            CompactIndex::open(invalid.as_slice()),
+            Err(IndexError::ValueSizeTooLarge(u64::MAX))
+        ));
+        data[0] = b'x';
+        assert!(matches!(
+            CompactIndex::open(data.as_slice()),
+            Err(IndexError::InvalidMagic)
+        ));
+    }
+}
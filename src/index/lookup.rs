@@ -0,0 +1,116 @@
+#[cfg(feature = "solana")]
+use crate::solana::{DecodeError, DecodedTransaction};
+use {
+    crate::{
+        index::{CidToOffsetAndSizeIndex, IndexError, SigToCidIndex, transaction_signature},
+        node::{Node, NodeReader, NodeWithCid, Nodes, OffsetAndSize, RawNode, Transaction},
+        rawtx::RawTransaction,
+        types::Signature,
+    },
+    cid::Cid,
+    std::collections::HashSet,
+    tokio::io::{AsyncRead, AsyncSeek},
+};
+
+/// Transaction found by the signature
+#[derive(Debug, Clone)]
+pub struct FoundTransaction {
+    pub cid: Cid,
+    pub position: OffsetAndSize,
+    pub transaction: Transaction,
+    /// DataFrame nodes referenced by the transaction data and metadata
+    pub nodes: Nodes,
+}
+
+impl FoundTransaction {
+    pub const fn slot(&self) -> u64 {
+        self.transaction.slot
+    }
+
+    #[cfg(feature = "solana")]
+    pub fn decode(&self) -> Result<DecodedTransaction, DecodeError> {
+        DecodedTransaction::decode(&self.transaction, &self.nodes)
+    }
+}
+
+/// Lookup of transactions with `sig-to-cid` and `cid-to-offset-and-size` indexes, every node is
+/// read with one seek
+#[derive(Debug)]
+pub struct TransactionLookup<D, R> {
+    pub sig_to_cid: SigToCidIndex<D>,
+    pub cid_to_offset: CidToOffsetAndSizeIndex<D>,
+    pub reader: NodeReader<R>,
+}
+
+impl<D: AsRef<[u8]>, R: AsyncRead + AsyncSeek + Unpin> TransactionLookup<D, R> {
+    pub const fn new(
+        sig_to_cid: SigToCidIndex<D>,
+        cid_to_offset: CidToOffsetAndSizeIndex<D>,
+        reader: NodeReader<R>,
+    ) -> Self {
+        Self {
+            sig_to_cid,
+            cid_to_offset,
+            reader,
+        }
+    }
+
+    /// Find the transaction, `None` if the signature is not in the index or the indexed
+    /// transaction has another signature
+    pub async fn find(
+        &mut self,
+        signature: &Signature,
+    ) -> Result<Option<FoundTransaction>, IndexError> {
+        let Some(cid) = self.sig_to_cid.get(signature)? else {
+            return Ok(None);
+        };
+        let (node, position) = self.read_node(&cid).await?;
+        let transaction = Transaction::try_from(node.get_data())?;
+        if matches!(
+            RawTransaction::parse_signature(&transaction.data.data),
+            Ok(found) if found != *signature
+        ) {
+            return Ok(None);
+        }
+
+        let mut nodes = Nodes::default();
+        let mut visited = HashSet::new();
+        let mut next_cids = [&transaction.data.next, &transaction.metadata.next]
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        while let Some(cid) = next_cids.pop() {
+            if !visited.insert(cid) {
+                continue;
+            }
+            let (node, _) = self.read_node(&cid).await?;
+            let node = NodeWithCid::try_from(&node)?;
+            if let Node::DataFrame(dataframe) = &node.node {
+                next_cids.extend(&dataframe.next);
+            }
+            nodes.push(node);
+        }
+        // the first frame can be shorter than the signature
+        if transaction_signature(&transaction, &nodes)? != *signature {
+            return Ok(None);
+        }
+
+        Ok(Some(FoundTransaction {
+            cid,
+            position,
+            transaction,
+            nodes,
+        }))
+    }
+
+    async fn read_node(&mut self, cid: &Cid) -> Result<(RawNode, OffsetAndSize), IndexError> {
+        let Some(position) = self.cid_to_offset.get(cid)? else {
+            return Err(IndexError::MissedNode(*cid));
+        };
+        match self.reader.read_node_at(position.offset).await? {
+            Some(node) if node.cid == *cid => Ok((node, position)),
+            _ => Err(IndexError::MissedNode(*cid)),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use {
+    crate::{
+        index::{CompactIndex, CompactIndexBuilder, IndexError, IndexInfo, open_index},
+        types::Signature,
+    },
+    cid::Cid,
+    tokio::io::AsyncWrite,
+};
+
+pub const KIND: &str = "sig-to-cid";
+/// Size of CIDv1 with dag-cbor codec and sha2-256 digest
+pub const VALUE_SIZE: usize = 36;
+
+/// Builder of the `sig-to-cid` index, key is the first signature of the transaction, value is
+/// the CID of the Transaction node
+#[derive(Debug)]
+pub struct SigToCidBuilder {
+    index: CompactIndexBuilder,
+}
+
+impl SigToCidBuilder {
+    pub fn new(info: &IndexInfo) -> Self {
+        Self {
+            index: CompactIndexBuilder::new(VALUE_SIZE, info.to_metadata(KIND)),
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn push(&mut self, signature: &Signature, cid: &Cid) -> Result<(), IndexError> {
+        self.index.push(signature.as_ref(), &cid.to_bytes())
+    }
+
+    pub async fn write<W: AsyncWrite + Unpin>(
+        &mut self,
+        writer: &mut W,
+    ) -> Result<u64, IndexError> {
+        self.index.write(writer).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SigToCidIndex<D> {
+    index: CompactIndex<D>,
+}
+
+impl<D: AsRef<[u8]>> SigToCidIndex<D> {
+    pub fn open(data: D) -> Result<Self, IndexError> {
+        open_index(data, KIND, VALUE_SIZE).map(|index| Self { index })
+    }
+
+    pub fn info(&self) -> Option<IndexInfo> {
+        IndexInfo::from_metadata(&self.index.header().metadata)
+    }
+
+    /// CID of the Transaction node, unknown signatures can return CID of another transaction
+    pub fn get(&self, signature: &Signature) -> Result<Option<Cid>, IndexError> {
+        match self.index.lookup(signature.as_ref())? {
+            Some(value) => Ok(Some(Cid::try_from(value)?)),
+            None => Ok(None),
+        }
+    }
+}
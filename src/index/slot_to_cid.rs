@@ -0,0 +1,66 @@
+use {
+    crate::index::{CompactIndex, CompactIndexBuilder, IndexError, IndexInfo, open_index},
+    cid::Cid,
+    tokio::io::AsyncWrite,
+};
+
+pub const KIND: &str = "slot-to-cid";
+/// Size of CIDv1 with dag-cbor codec and sha2-256 digest
+pub const VALUE_SIZE: usize = 36;
+
+/// Builder of the `slot-to-cid` index, key is the slot as u64 little-endian, value is the
+/// CID of the Block node
+#[derive(Debug)]
+pub struct SlotToCidBuilder {
+    index: CompactIndexBuilder,
+}
+
+impl SlotToCidBuilder {
+    pub fn new(info: &IndexInfo) -> Self {
+        Self {
+            index: CompactIndexBuilder::new(VALUE_SIZE, info.to_metadata(KIND)),
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn push(&mut self, slot: u64, cid: &Cid) -> Result<(), IndexError> {
+        self.index.push(&slot.to_le_bytes(), &cid.to_bytes())
+    }
+
+    pub async fn write<W: AsyncWrite + Unpin>(
+        &mut self,
+        writer: &mut W,
+    ) -> Result<u64, IndexError> {
+        self.index.write(writer).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SlotToCidIndex<D> {
+    index: CompactIndex<D>,
+}
+
+impl<D: AsRef<[u8]>> SlotToCidIndex<D> {
+    pub fn open(data: D) -> Result<Self, IndexError> {
+        open_index(data, KIND, VALUE_SIZE).map(|index| Self { index })
+    }
+
+    pub fn info(&self) -> Option<IndexInfo> {
+        IndexInfo::from_metadata(&self.index.header().metadata)
+    }
+
+    /// CID of the Block node, slots without blocks can return CID of another block
+    pub fn get(&self, slot: u64) -> Result<Option<Cid>, IndexError> {
+        match self.index.lookup(&slot.to_le_bytes())? {
+            Some(value) => Ok(Some(Cid::try_from(value)?)),
+            None => Ok(None),
+        }
+    }
+}
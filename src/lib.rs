@@ -1,3 +1,14 @@
+pub mod bundle;
+pub mod checksum;
+#[cfg(feature = "index")]
+pub mod index;
 pub mod node;
+pub mod rawtx;
+#[cfg(feature = "solana")]
+pub mod solana;
+pub mod streaming;
+pub mod types;
 pub mod util;
+pub mod validate;
 pub mod varint;
+pub mod walker;
@@ -1,18 +1,20 @@
 use {
-    crate::{util, varint},
+    crate::{
+        checksum::{Checksum, ChecksumKind, DEFAULT_CHECKSUMS},
+        util, varint,
+    },
     cid::Cid,
-    crc::{CRC_64_GO_ISO, Crc},
-    fnv::FnvHasher,
     indexmap::IndexMap,
-    std::{fmt, hash::Hasher, io},
+    std::{collections::HashSet, fmt, io},
     thiserror::Error,
-    tokio::io::AsyncRead,
+    tokio::io::{AsyncRead, AsyncSeek, AsyncSeekExt},
 };
 pub use {
     block::{Block, Shredding, SlotMeta},
-    dataframe::DataFrame,
+    dataframe::{DataFrame, DataFramesBuf},
     entry::Entry,
-    epoch::Epoch,
+    epoch::{Epoch, SLOTS_PER_EPOCH},
+    header::CarHeader,
     rewards::Rewards,
     subset::Subset,
     transaction::Transaction,
@@ -22,12 +24,14 @@ mod block;
 mod dataframe;
 mod entry;
 mod epoch;
+mod header;
 mod rewards;
 mod subset;
 mod transaction;
 
 const MAX_ALLOWED_HEADER_SIZE: usize = 1024;
 const MAX_ALLOWED_SECTION_SIZE: usize = 32 << 20; // 32MiB
+const MAX_ALLOWED_DATAFRAMES_SIZE: usize = 1 << 30; // 1GiB
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Kind {
@@ -189,11 +193,58 @@ impl RawNode {
     pub fn get_data(&self) -> &[u8] {
         &self.data[self.data_offset..]
     }
+
+    /// Read node kind from the CBOR array header and the first integer without full decoding
+    pub fn kind(&self) -> Result<Kind, NodeError> {
+        let mut data = self.get_data();
+        let kind = match util::cbor::peek_header(&mut data) {
+            Some((util::cbor::MAJOR_ARRAY, _)) => match util::cbor::peek_header(&mut data) {
+                Some((util::cbor::MAJOR_UNSIGNED, kind)) => Some(kind),
+                _ => None,
+            },
+            _ => None,
+        };
+        kind.and_then(Kind::from_u64)
+            .ok_or(NodeError::UnknownKind(kind))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KindFilter(u8);
+
+impl KindFilter {
+    pub const fn all() -> Self {
+        Self(0x7f)
+    }
+
+    pub const fn with(self, kind: Kind) -> Self {
+        Self(self.0 | (1 << kind.to_u64()))
+    }
+
+    pub const fn contains(&self, kind: Kind) -> bool {
+        self.0 & (1 << kind.to_u64()) != 0
+    }
+}
+
+impl FromIterator<Kind> for KindFilter {
+    fn from_iter<T: IntoIterator<Item = Kind>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::default(), Self::with)
+    }
+}
+
+/// Position of the section in the CAR file, `offset` points to the section length prefix and
+/// `size` includes the prefix
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OffsetAndSize {
+    pub offset: u64,
+    pub size: u64,
 }
 
 pub struct NodeReader<R> {
     reader: R,
     header: Vec<u8>,
+    filter: KindFilter,
+    offset: u64,
 }
 
 impl<R> fmt::Debug for NodeReader<R> {
@@ -207,9 +258,22 @@ impl<R: AsyncRead + Unpin> NodeReader<R> {
         Self {
             reader,
             header: vec![],
+            filter: KindFilter::all(),
+            offset: 0,
         }
     }
 
+    /// Number of bytes consumed from the reader, offset of the next section after the header
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Skip nodes with kinds not in the filter in [`NodeReader::read_node`]
+    pub const fn with_filter(mut self, filter: KindFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
     pub async fn read_header(&mut self) -> Result<&[u8], NodeError> {
         if self.header.is_empty() {
             let header_length = varint::read(&mut self.reader).await? as usize;
@@ -218,16 +282,36 @@ impl<R: AsyncRead + Unpin> NodeReader<R> {
             }
 
             self.header = util::read_exact(&mut self.reader, header_length).await?;
+            self.offset +=
+                (varint::encoded_len_varint(header_length as u64) + header_length) as u64;
         }
 
         Ok(&self.header)
     }
 
     pub async fn read_node(&mut self) -> Result<Option<RawNode>, NodeError> {
+        Ok(self.read_node_with_offset().await?.map(|(node, _)| node))
+    }
+
+    /// Same as [`NodeReader::read_node`], but also returns position of the node section
+    pub async fn read_node_with_offset(
+        &mut self,
+    ) -> Result<Option<(RawNode, OffsetAndSize)>, NodeError> {
         if self.header.is_empty() {
             self.read_header().await?;
         };
 
+        loop {
+            let Some((node, position)) = self.read_section().await? else {
+                return Ok(None);
+            };
+            if self.filter == KindFilter::all() || self.filter.contains(node.kind()?) {
+                return Ok(Some((node, position)));
+            }
+        }
+    }
+
+    async fn read_section(&mut self) -> Result<Option<(RawNode, OffsetAndSize)>, NodeError> {
         // read and decode the uvarint prefix (length of CID + data)
         let section_size = match varint::read(&mut self.reader).await {
             Ok(size) => size as usize,
@@ -241,7 +325,27 @@ impl<R: AsyncRead + Unpin> NodeReader<R> {
         }
 
         let section = util::read_exact(&mut self.reader, section_size).await?;
-        RawNode::new_from_vec(section).map(Some)
+        let position = OffsetAndSize {
+            offset: self.offset,
+            size: (varint::encoded_len_varint(section_size as u64) + section_size) as u64,
+        };
+        self.offset += position.size;
+        RawNode::new_from_vec(section).map(|node| Some((node, position)))
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> NodeReader<R> {
+    /// Read the node section at `offset`, the filter is not applied, sequential reading continues
+    /// from the next section
+    ///
+    /// Offsets are usually taken from [`NodeReader::read_node_with_offset`] or the index.
+    pub async fn read_node_at(&mut self, offset: u64) -> Result<Option<RawNode>, NodeError> {
+        if self.header.is_empty() {
+            self.reader.seek(io::SeekFrom::Start(0)).await?;
+            self.read_header().await?;
+        }
+        self.offset = self.reader.seek(io::SeekFrom::Start(offset)).await?;
+        Ok(self.read_section().await?.map(|(node, _)| node))
     }
 }
 
@@ -274,12 +378,58 @@ impl Nodes {
     pub fn reassemble_dataframes<'a>(
         &'a self,
         dataframe: &'a DataFrame,
-    ) -> Result<Vec<u8>, ReassableError> {
+    ) -> Result<(Vec<u8>, ChecksumKind), ReassableError> {
+        self.reassemble_dataframes_buf(dataframe)
+            .map(|(buf, checksum)| (buf.into_vec(), checksum))
+    }
+
+    /// Reassemble frames without copying data, the checksum is computed chunk by chunk
+    pub fn reassemble_dataframes_buf<'a>(
+        &'a self,
+        dataframe: &'a DataFrame,
+    ) -> Result<(DataFramesBuf<'a>, ChecksumKind), ReassableError> {
+        self.reassemble_dataframes_buf_with(dataframe, DEFAULT_CHECKSUMS)
+    }
+
+    /// Reassemble frames and validate data with the first matched checksum function
+    pub fn reassemble_dataframes_buf_with<'a, C: Checksum + ?Sized>(
+        &'a self,
+        dataframe: &'a DataFrame,
+        checksums: &[&C],
+    ) -> Result<(DataFramesBuf<'a>, ChecksumKind), ReassableError> {
+        let dataframes = self.collect_dataframes(dataframe)?;
+        let buf = DataFramesBuf::new(&dataframes);
+
+        let Some(expected) = dataframe.hash else {
+            return Ok((buf, ChecksumKind::None));
+        };
+
+        let mut computed = Vec::with_capacity(checksums.len());
+        for checksum in checksums {
+            let value = checksum.compute(&mut buf.chunks());
+            if value == expected {
+                return Ok((buf, checksum.kind()));
+            }
+            computed.push((checksum.kind(), value));
+        }
+        Err(ReassableError::InvalidHash { computed, expected })
+    }
+
+    /// Collect all frames linked from the first one, ordered by index
+    fn collect_dataframes<'a>(
+        &'a self,
+        dataframe: &'a DataFrame,
+    ) -> Result<Vec<&'a DataFrame>, ReassableError> {
         let mut dataframes = vec![dataframe];
+        let mut size = dataframe.data.len();
         let total = dataframe.total.unwrap_or_default() as usize;
         if total > 1 {
+            let mut visited = HashSet::new();
             let mut next_cids = dataframe.next.clone();
             while let Some(cid) = next_cids.pop() {
+                if !visited.insert(cid) {
+                    return Err(ReassableError::Cycle(cid));
+                }
                 let Some(node) = self.nodes.get(&cid) else {
                     return Err(ReassableError::MissedCid(cid));
                 };
@@ -288,6 +438,14 @@ impl Nodes {
                 };
                 next_cids.extend(&dataframe.next);
                 dataframes.push(dataframe);
+
+                size += dataframe.data.len();
+                if size > MAX_ALLOWED_DATAFRAMES_SIZE {
+                    return Err(ReassableError::DataTooLarge(size));
+                }
+                if dataframes.len() > total {
+                    break;
+                }
             }
             if dataframes.len() != total {
                 return Err(ReassableError::InvalidNumberOfDataFrames {
@@ -295,47 +453,25 @@ impl Nodes {
                     found: dataframes.len(),
                 });
             }
-            dataframes.sort_by_key(|frame| frame.index.unwrap_or_default());
-        }
 
-        let data: Vec<u8> = dataframes
-            .iter()
-            .flat_map(|frame| frame.data.iter())
-            .copied()
-            .collect();
-
-        if let Some(expected) = dataframe.hash {
-            let crc64 = get_crc64(&data);
-            if crc64 != expected {
-                // maybe it's the legacy checksum function?
-                let fnv = get_fnv(&data);
-                if fnv != expected {
-                    return Err(ReassableError::InvalidHash {
-                        crc64,
-                        fnv,
-                        expected,
-                    });
+            let mut indices = vec![false; total];
+            for frame in dataframes.iter() {
+                match frame.index {
+                    Some(index) if (index as usize) < total => {
+                        if indices[index as usize] {
+                            return Err(ReassableError::DuplicateIndex(index));
+                        }
+                        indices[index as usize] = true;
+                    }
+                    index => return Err(ReassableError::InvalidIndex { index, total }),
                 }
             }
+            dataframes.sort_by_key(|frame| frame.index.unwrap_or_default());
         }
-
-        Ok(data)
+        Ok(dataframes)
     }
 }
 
-fn get_crc64(data: &[u8]) -> u64 {
-    let crc = Crc::<u64>::new(&CRC_64_GO_ISO);
-    let mut digest = crc.digest();
-    digest.update(data);
-    digest.finalize()
-}
-
-fn get_fnv(data: &[u8]) -> u64 {
-    let mut hasher = FnvHasher::default();
-    hasher.write(data);
-    hasher.finish()
-}
-
 #[derive(Debug, Error)]
 pub enum NodeError {
     // read
@@ -355,6 +491,8 @@ pub enum NodeError {
         path: &'static str,
         kind: &'static str,
     },
+    #[error("invalid hash length for {path:?}: {len} (expected {expected})", expected = crate::types::HASH_BYTES)]
+    InvalidHashLength { path: &'static str, len: usize },
     #[error("too much items in the array")]
     UnexpectedCborValues,
     #[error("invalid node kind: {node:?} (expected: {expected:?})")]
@@ -402,6 +540,159 @@ pub enum ReassableError {
     InvalidNumberOfDataFrames { expected: usize, found: usize },
     #[error("invalid node kind: {0:?} (expected DataFrame)")]
     InvalidNode(Kind),
-    #[error("invalid hash: crc64/{crc64} fnv/{fnv} (expected: {expected}")]
-    InvalidHash { crc64: u64, fnv: u64, expected: u64 },
+    #[error("cycle detected, cid visited twice: {0}")]
+    Cycle(Cid),
+    #[error("duplicate DataFrame index: {0}")]
+    DuplicateIndex(u64),
+    #[error("invalid DataFrame index: {index:?} (total: {total})")]
+    InvalidIndex { index: Option<u64>, total: usize },
+    #[error("reassembled data is too large: {0} (max {max})", max = MAX_ALLOWED_DATAFRAMES_SIZE)]
+    DataTooLarge(usize),
+    #[error("invalid hash: {computed:?} (expected: {expected})")]
+    InvalidHash {
+        computed: Vec<(ChecksumKind, u64)>,
+        expected: u64,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            checksum::ChecksumKind,
+            node::{
+                DataFrame, Kind, KindFilter, Node, NodeError, NodeWithCid, Nodes, RawNode,
+                ReassableError,
+            },
+            util::tests::{decode_cid, decode_hex, make_cid},
+        },
+        cid::Cid,
+    };
+
+    fn build_dataframes(frames: impl IntoIterator<Item = (u8, Option<u64>, Vec<u8>)>) -> Nodes {
+        let mut nodes = Nodes::default();
+        for (cid, index, next) in frames {
+            nodes.push(NodeWithCid {
+                cid: make_cid(cid),
+                node: Node::DataFrame(DataFrame {
+                    index,
+                    total: Some(3),
+                    data: vec![index.unwrap_or_default() as u8],
+                    next: next.into_iter().map(make_cid).collect(),
+                    ..Default::default()
+                }),
+            });
+        }
+        nodes
+    }
+
+    #[test]
+    fn test_reassemble_dataframes() {
+        let first = DataFrame {
+            index: Some(0),
+            total: Some(3),
+            data: vec![0],
+            next: vec![make_cid(1), make_cid(2)],
+            ..Default::default()
+        };
+
+        let nodes = build_dataframes([(1, Some(1), vec![]), (2, Some(2), vec![])]);
+        assert_eq!(
+            nodes.reassemble_dataframes(&first).expect("valid frames"),
+            (vec![0, 1, 2], ChecksumKind::None)
+        );
+        for (hash, kind) in [
+            (7826780682310385664, ChecksumKind::Crc64),
+            (15657232601398921512, ChecksumKind::Fnv),
+        ] {
+            let first = DataFrame {
+                hash: Some(hash),
+                ..first.clone()
+            };
+            assert_eq!(
+                nodes.reassemble_dataframes(&first).expect("valid frames").1,
+                kind
+            );
+        }
+        let first_invalid = DataFrame {
+            hash: Some(0),
+            ..first.clone()
+        };
+        assert!(matches!(
+            nodes.reassemble_dataframes(&first_invalid),
+            Err(ReassableError::InvalidHash { computed, expected: 0 }) if computed.len() == 2
+        ));
+
+        let nodes = build_dataframes([(1, Some(1), vec![]), (2, Some(2), vec![1])]);
+        assert!(matches!(
+            nodes.reassemble_dataframes(&first),
+            Err(ReassableError::Cycle(cid)) if cid == make_cid(1)
+        ));
+
+        let nodes = build_dataframes([(1, Some(1), vec![]), (2, Some(1), vec![])]);
+        assert!(matches!(
+            nodes.reassemble_dataframes(&first),
+            Err(ReassableError::DuplicateIndex(1))
+        ));
+
+        let nodes = build_dataframes([(1, Some(1), vec![]), (2, Some(3), vec![])]);
+        assert!(matches!(
+            nodes.reassemble_dataframes(&first),
+            Err(ReassableError::InvalidIndex {
+                index: Some(3),
+                total: 3
+            })
+        ));
+
+        let nodes = build_dataframes([
+            (1, Some(1), vec![]),
+            (2, Some(2), vec![3]),
+            (3, None, vec![]),
+        ]);
+        assert!(matches!(
+            nodes.reassemble_dataframes(&first),
+            Err(ReassableError::InvalidNumberOfDataFrames {
+                expected: 3,
+                found: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn test_raw_node_kind() {
+        let cid = decode_cid("bafyreibysst7x3lvzdrllbspoob5z2epcrb6bmzqqlcxxysvku4cmvdk4e");
+        for (bytes, kind) in [
+            (
+                decode_hex(
+                    "84011930d458203a43cd82e140873740fde924da4125ac30e2fec5eb92344dbb2bb4776973feec80",
+                ),
+                Kind::Entry,
+            ),
+            (
+                decode_hex("86063b4630c0a8d52653c101024620776f726c6480"),
+                Kind::DataFrame,
+            ),
+            (
+                decode_hex("83051a010114848506f6f6f65528b52ffd04004100000000000000000000bb1bdbca"),
+                Kind::Rewards,
+            ),
+        ] {
+            assert_eq!(RawNode::new(cid, bytes).kind().expect("valid kind"), kind);
+        }
+
+        for bytes in [decode_hex("8307"), decode_hex("a10102"), vec![]] {
+            assert!(matches!(
+                RawNode::new(Cid::default(), bytes).kind(),
+                Err(NodeError::UnknownKind(_))
+            ));
+        }
+
+        let filter = [Kind::Block, Kind::Rewards]
+            .into_iter()
+            .collect::<KindFilter>();
+        assert!(filter.contains(Kind::Block));
+        assert!(filter.contains(Kind::Rewards));
+        assert!(!filter.contains(Kind::Transaction));
+        assert!(KindFilter::all().contains(Kind::DataFrame));
+    }
 }
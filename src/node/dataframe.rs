@@ -3,7 +3,9 @@ use {
         node::{Kind, NodeError},
         util,
     },
+    bytes::Buf,
     cid::Cid,
+    std::io,
 };
 
 // # DataFrame is a chunk of data that is part of a larger whole. It contains
@@ -100,13 +102,129 @@ impl TryFrom<serde_cbor::Value> for DataFrame {
     }
 }
 
+/// Data of ordered frames without copying, implements [`Buf`] and [`io::Read`]
+#[derive(Debug, Default, Clone)]
+pub struct DataFramesBuf<'a> {
+    chunks: Vec<&'a [u8]>,
+    chunk_index: usize,
+    chunk_offset: usize,
+    remaining: usize,
+}
+
+impl<'a> DataFramesBuf<'a> {
+    pub fn new(frames: &[&'a DataFrame]) -> Self {
+        let chunks = frames
+            .iter()
+            .map(|frame| frame.data.as_slice())
+            .filter(|chunk| !chunk.is_empty())
+            .collect::<Vec<_>>();
+        let remaining = chunks.iter().map(|chunk| chunk.len()).sum();
+        Self {
+            chunks,
+            chunk_index: 0,
+            chunk_offset: 0,
+            remaining,
+        }
+    }
+
+    /// Not consumed data, chunk by chunk
+    pub fn chunks(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.chunks
+            .get(self.chunk_index..)
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| match index {
+                0 => &chunk[self.chunk_offset..],
+                _ => chunk,
+            })
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.remaining);
+        for chunk in self.chunks() {
+            data.extend_from_slice(chunk);
+        }
+        data
+    }
+}
+
+impl Buf for DataFramesBuf<'_> {
+    fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn chunk(&self) -> &[u8] {
+        match self.chunks.get(self.chunk_index) {
+            Some(chunk) => &chunk[self.chunk_offset..],
+            None => &[],
+        }
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        assert!(
+            cnt <= self.remaining,
+            "cannot advance past `remaining`: {cnt} <= {}",
+            self.remaining
+        );
+        self.remaining -= cnt;
+        while cnt > 0 {
+            let left = self.chunks[self.chunk_index].len() - self.chunk_offset;
+            if cnt < left {
+                self.chunk_offset += cnt;
+                break;
+            }
+            cnt -= left;
+            self.chunk_index += 1;
+            self.chunk_offset = 0;
+        }
+    }
+}
+
+impl io::Read for DataFramesBuf<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let chunk = self.chunk();
+        let size = chunk.len().min(buf.len());
+        buf[..size].copy_from_slice(&chunk[..size]);
+        self.advance(size);
+        Ok(size)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{
-        node::DataFrame,
-        util::tests::{decode_cids, decode_hex},
+    use {
+        crate::{
+            node::{DataFrame, DataFramesBuf},
+            util::tests::{decode_cids, decode_hex},
+        },
+        bytes::Buf,
+        std::io::Read,
     };
 
+    #[test]
+    fn test_buf() {
+        let frames = [vec![1, 2, 3], vec![], vec![4], vec![5, 6]].map(|data| DataFrame {
+            data,
+            ..Default::default()
+        });
+        let frames = frames.iter().collect::<Vec<_>>();
+
+        let mut buf = DataFramesBuf::new(&frames);
+        assert_eq!(buf.remaining(), 6);
+        buf.advance(2);
+        assert_eq!(buf.chunk(), [3]);
+        buf.advance(2);
+        assert_eq!(buf.chunks().collect::<Vec<_>>(), [&[5, 6]]);
+        assert_eq!(buf.clone().into_vec(), [5, 6]);
+
+        let mut data = vec![];
+        DataFramesBuf::new(&frames)
+            .read_to_end(&mut data)
+            .expect("infallible read");
+        assert_eq!(data, [1, 2, 3, 4, 5, 6]);
+    }
+
     #[test]
     fn test_decoding() {
         for (bytes, frame) in [
@@ -1,6 +1,7 @@
 use {
     crate::{
         node::{Kind, NodeError},
+        types::Hash,
         util,
     },
     cid::Cid,
@@ -16,7 +17,7 @@ use {
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct Entry {
     pub num_hashes: u64,
-    pub hash: Vec<u8>,
+    pub hash: Hash,
     pub transactions: Vec<Cid>,
 }
 
@@ -43,7 +44,7 @@ impl TryFrom<serde_cbor::Value> for Entry {
                     Kind::Entry,
                 )?,
                 1 => node.num_hashes = util::cbor::get_int(value, "Entry::num_hashes")? as u64,
-                2 => node.hash = util::cbor::get_bytes(value, "Entry::hash")?,
+                2 => node.hash = util::cbor::get_hash(value, "Entry::hash")?,
                 3 => {
                     node.transactions = util::cbor::get_array_cids(
                         value,
@@ -62,7 +63,7 @@ impl TryFrom<serde_cbor::Value> for Entry {
 mod tests {
     use crate::{
         node::Entry,
-        util::tests::{decode_cids, decode_hex},
+        util::tests::{decode_cids, decode_hash, decode_hex},
     };
 
     #[test]
@@ -74,7 +75,7 @@ mod tests {
                 ),
                 Entry {
                     num_hashes: 12500,
-                    hash: decode_hex(
+                    hash: decode_hash(
                         "3a43cd82e140873740fde924da4125ac30e2fec5eb92344dbb2bb4776973feec",
                     ),
                     transactions: vec![],
@@ -86,7 +87,7 @@ mod tests {
                 ),
                 Entry {
                     num_hashes: 12500,
-                    hash: decode_hex(
+                    hash: decode_hash(
                         "b12c324e55fb861ce6ef0d315ed3115bea52f6bec83cf09c9872c70de69fdfea",
                     ),
                     transactions: vec![],
@@ -98,7 +99,7 @@ mod tests {
                 ),
                 Entry {
                     num_hashes: 12500,
-                    hash: decode_hex(
+                    hash: decode_hash(
                         "475c39d0431d1479a35fa3499e0a8dd6e472254f5f734408a896a9fda5219995",
                     ),
                     transactions: vec![],
@@ -110,7 +111,7 @@ mod tests {
                 ),
                 Entry {
                     num_hashes: 12179,
-                    hash: decode_hex(
+                    hash: decode_hash(
                         "87b3f95ad785a5e8c7b5ffae44b37c200c27d5464870545489560c217a48d798",
                     ),
                     transactions: decode_cids([
@@ -6,6 +6,8 @@ use {
     cid::Cid,
 };
 
+pub const SLOTS_PER_EPOCH: u64 = 432_000;
+
 // # Epoch is the top-level data structure in the DAG. It contains a list of
 // # subsets, which in turn contain a list of blocks. Each block contains a list
 // # of entries, which in turn contain a list of transactions.
@@ -25,6 +27,19 @@ pub struct Epoch {
     pub subsets: Vec<Cid>,
 }
 
+impl Epoch {
+    /// First and last slots of the epoch, `None` if the epoch is too large
+    pub const fn slot_range(&self) -> Option<(u64, u64)> {
+        let Some(first) = self.epoch.checked_mul(SLOTS_PER_EPOCH) else {
+            return None;
+        };
+        match first.checked_add(SLOTS_PER_EPOCH - 1) {
+            Some(last) => Some((first, last)),
+            None => None,
+        }
+    }
+}
+
 impl TryFrom<&[u8]> for Epoch {
     type Error = NodeError;
 
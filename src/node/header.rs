@@ -0,0 +1,76 @@
+use {
+    crate::{node::NodeError, util},
+    cid::Cid,
+    serde_cbor::Value,
+};
+
+// type CarHeader struct {
+//   roots   [&Any]
+//   version Int
+// }
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CarHeader {
+    pub roots: Vec<Cid>,
+    pub version: u64,
+}
+
+impl TryFrom<&[u8]> for CarHeader {
+    type Error = NodeError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Self::try_from(serde_cbor::from_slice::<Value>(value)?)
+    }
+}
+
+impl TryFrom<Value> for CarHeader {
+    type Error = NodeError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let Value::Map(map) = value else {
+            return Err(NodeError::UnexpectedCborValue {
+                path: "CarHeader",
+                kind: "Map",
+            });
+        };
+
+        let mut header = Self::default();
+        for (key, value) in map {
+            match key {
+                Value::Text(key) if key == "roots" => {
+                    header.roots =
+                        util::cbor::get_array_cids(value, "CarHeader::roots", "CarHeader::roots[]")?
+                }
+                Value::Text(key) if key == "version" => {
+                    header.version = util::cbor::get_int(value, "CarHeader::version")? as u64
+                }
+                _ => {}
+            }
+        }
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        node::CarHeader,
+        util::tests::{decode_cids, decode_hex},
+    };
+
+    #[test]
+    fn test_decoding() {
+        #[allow(clippy::single_element_loop)]
+        for (bytes, header) in [(
+            decode_hex(
+                "a265726f6f747381d82a5825000171122059760f2fd3f4944861167ddf07169a83ef4a44731953b567bcdd4ab8ab31f8af6776657273696f6e01",
+            ),
+            CarHeader {
+                roots: decode_cids(["bafyreiczoyhs7u7usregcft534drngud55fei4yzko2wppg5jk4kwmpyv4"]),
+                version: 1,
+            },
+        )] {
+            let node = CarHeader::try_from(bytes.as_ref()).expect("valid header");
+            assert_eq!(node, header);
+        }
+    }
+}
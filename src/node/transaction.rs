@@ -1,5 +1,5 @@
 use crate::{
-    node::{DataFrame, Kind, NodeError},
+    node::{DataFrame, Kind, NodeError, Nodes, ReassableError},
     util,
 };
 
@@ -58,13 +58,62 @@ impl TryFrom<serde_cbor::Value> for Transaction {
     }
 }
 
+impl Transaction {
+    /// Reassembled transaction data (bincode encoded `VersionedTransaction`)
+    pub fn payload(&self, nodes: &Nodes) -> Result<Vec<u8>, ReassableError> {
+        nodes
+            .reassemble_dataframes(&self.data)
+            .map(|(data, _checksum)| data)
+    }
+
+    /// Reassembled transaction metadata (zstd compressed protobuf or bincode)
+    pub fn metadata(&self, nodes: &Nodes) -> Result<Vec<u8>, ReassableError> {
+        nodes
+            .reassemble_dataframes(&self.metadata)
+            .map(|(data, _checksum)| data)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        node::{DataFrame, Transaction},
-        util::tests::decode_hex,
+        node::{DataFrame, Node, NodeWithCid, Nodes, Transaction},
+        util::tests::{decode_hex, make_cid},
     };
 
+    #[test]
+    fn test_payload() {
+        let mut nodes = Nodes::default();
+        nodes.push(NodeWithCid {
+            cid: make_cid(1),
+            node: Node::DataFrame(DataFrame {
+                hash: Some(7826780682310385664),
+                index: Some(1),
+                total: Some(2),
+                data: vec![1, 2],
+                next: vec![],
+            }),
+        });
+        let tx = Transaction {
+            data: DataFrame {
+                hash: Some(7826780682310385664),
+                index: Some(0),
+                total: Some(2),
+                data: vec![0],
+                next: vec![make_cid(1)],
+            },
+            metadata: DataFrame {
+                data: vec![3, 4],
+                ..Default::default()
+            },
+            slot: 1,
+            index: Some(0),
+        };
+        assert_eq!(tx.payload(&nodes).expect("valid payload"), [0, 1, 2]);
+        assert_eq!(tx.metadata(&nodes).expect("valid metadata"), [3, 4]);
+        assert!(tx.payload(&Nodes::default()).is_err());
+    }
+
     #[test]
     fn test_decoding() {
         for (bytes, frame) in [
@@ -0,0 +1,243 @@
+use {
+    crate::types::{HASH_BYTES, Hash, SIGNATURE_BYTES, Signature},
+    thiserror::Error,
+};
+
+const PUBKEY_BYTES: usize = 32;
+const MESSAGE_VERSION_PREFIX: u8 = 0x80;
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum RawTransactionError {
+    #[error("unexpected end of data at {0}")]
+    UnexpectedEnd(usize),
+    #[error("invalid compact-u16 at {0}")]
+    InvalidCompactU16(usize),
+    #[error("no signatures")]
+    NoSignatures,
+}
+
+/// Decode compact-u16 (aka shortvec) length, returns value and number of consumed bytes
+pub fn decode_compact_u16(bytes: &[u8]) -> Option<(u16, usize)> {
+    let mut value = 0u32;
+    for (index, byte) in bytes.iter().take(3).enumerate() {
+        let byte = *byte as u32;
+        // last byte can carry only 2 bits, zero continuation bytes are not allowed
+        if (index == 2 && byte > 0x03) || (index > 0 && byte == 0) {
+            return None;
+        }
+        value |= (byte & 0x7f) << (index * 7);
+        if byte & 0x80 == 0 {
+            return Some((value as u16, index + 1));
+        }
+    }
+    None
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawMessageHeader {
+    pub num_required_signatures: u8,
+    pub num_readonly_signed_accounts: u8,
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+/// Bincode encoded `VersionedTransaction` parsed up to the recent blockhash, without copying
+/// and without decoding of instructions and address table lookups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawTransaction<'a> {
+    signatures: &'a [u8],
+    /// `None` for legacy messages
+    pub version: Option<u8>,
+    pub header: RawMessageHeader,
+    account_keys: &'a [u8],
+    pub recent_blockhash: Hash,
+}
+
+impl<'a> RawTransaction<'a> {
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, RawTransactionError> {
+        let mut offset = 0;
+
+        let signatures_count = Self::read_compact_u16(bytes, &mut offset)?;
+        if signatures_count == 0 {
+            return Err(RawTransactionError::NoSignatures);
+        }
+        let signatures = Self::read_bytes(bytes, &mut offset, signatures_count * SIGNATURE_BYTES)?;
+
+        let mut version = None;
+        let first = Self::read_bytes(bytes, &mut offset, 1)?[0];
+        let header_first = if first & MESSAGE_VERSION_PREFIX != 0 {
+            version = Some(first & !MESSAGE_VERSION_PREFIX);
+            Self::read_bytes(bytes, &mut offset, 1)?[0]
+        } else {
+            first
+        };
+        let header_rest = Self::read_bytes(bytes, &mut offset, 2)?;
+        let header = RawMessageHeader {
+            num_required_signatures: header_first,
+            num_readonly_signed_accounts: header_rest[0],
+            num_readonly_unsigned_accounts: header_rest[1],
+        };
+
+        let account_keys_count = Self::read_compact_u16(bytes, &mut offset)?;
+        let account_keys = Self::read_bytes(bytes, &mut offset, account_keys_count * PUBKEY_BYTES)?;
+        let recent_blockhash = Self::read_bytes(bytes, &mut offset, HASH_BYTES)?
+            .try_into()
+            .expect("valid length");
+
+        Ok(Self {
+            signatures,
+            version,
+            header,
+            account_keys,
+            recent_blockhash,
+        })
+    }
+
+    /// Parse only the first signature
+    pub fn parse_signature(bytes: &[u8]) -> Result<Signature, RawTransactionError> {
+        let mut offset = 0;
+        if Self::read_compact_u16(bytes, &mut offset)? == 0 {
+            return Err(RawTransactionError::NoSignatures);
+        }
+        Ok(Self::read_bytes(bytes, &mut offset, SIGNATURE_BYTES)?
+            .try_into()
+            .expect("valid length"))
+    }
+
+    fn read_compact_u16(bytes: &[u8], offset: &mut usize) -> Result<usize, RawTransactionError> {
+        let rest = bytes.get(*offset..).unwrap_or_default();
+        let (value, len) = decode_compact_u16(rest).ok_or(if rest.len() < 3 {
+            RawTransactionError::UnexpectedEnd(*offset)
+        } else {
+            RawTransactionError::InvalidCompactU16(*offset)
+        })?;
+        *offset += len;
+        Ok(value as usize)
+    }
+
+    fn read_bytes<'b>(
+        bytes: &'b [u8],
+        offset: &mut usize,
+        len: usize,
+    ) -> Result<&'b [u8], RawTransactionError> {
+        let slice = bytes
+            .get(*offset..*offset + len)
+            .ok_or(RawTransactionError::UnexpectedEnd(*offset))?;
+        *offset += len;
+        Ok(slice)
+    }
+
+    pub const fn signatures_count(&self) -> usize {
+        self.signatures.len() / SIGNATURE_BYTES
+    }
+
+    pub fn signature(&self, index: usize) -> Option<Signature> {
+        self.signatures
+            .chunks_exact(SIGNATURE_BYTES)
+            .nth(index)
+            .map(|bytes| bytes.try_into().expect("valid length"))
+    }
+
+    pub fn signatures(&self) -> impl Iterator<Item = Signature> + 'a {
+        self.signatures
+            .chunks_exact(SIGNATURE_BYTES)
+            .map(|bytes| bytes.try_into().expect("valid length"))
+    }
+
+    pub const fn account_keys_count(&self) -> usize {
+        self.account_keys.len() / PUBKEY_BYTES
+    }
+
+    /// Static account key, keys loaded from address lookup tables are not included
+    pub fn account_key(&self, index: usize) -> Option<&'a [u8; PUBKEY_BYTES]> {
+        self.account_keys
+            .chunks_exact(PUBKEY_BYTES)
+            .nth(index)
+            .map(|bytes| bytes.try_into().expect("valid length"))
+    }
+
+    /// Static account keys, keys loaded from address lookup tables are not included
+    pub fn account_keys(&self) -> impl Iterator<Item = &'a [u8; PUBKEY_BYTES]> + 'a {
+        self.account_keys
+            .chunks_exact(PUBKEY_BYTES)
+            .map(|bytes| bytes.try_into().expect("valid length"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{RawMessageHeader, RawTransaction, RawTransactionError, decode_compact_u16},
+        crate::types::{Hash, Signature},
+    };
+
+    #[test]
+    fn test_compact_u16() {
+        for (bytes, expected) in [
+            (&[0x00][..], Some((0, 1))),
+            (&[0x7f], Some((0x7f, 1))),
+            (&[0x80, 0x01], Some((0x80, 2))),
+            (&[0xff, 0x7f], Some((0x3fff, 2))),
+            (&[0x80, 0x80, 0x01], Some((0x4000, 3))),
+            (&[0xff, 0xff, 0x03], Some((0xffff, 3))),
+            (&[0xff, 0xff, 0x04], None),
+            (&[0x80, 0x00], None),
+            (&[0x80], None),
+        ] {
+            assert_eq!(decode_compact_u16(bytes), expected, "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for version in [None, Some(0)] {
+            let mut bytes = vec![2];
+            bytes.extend_from_slice(&[1; 64]);
+            bytes.extend_from_slice(&[2; 64]);
+            if let Some(version) = version {
+                bytes.push(0x80 | version);
+            }
+            bytes.extend_from_slice(&[2, 0, 1, 3]);
+            for key in 0..3 {
+                bytes.extend_from_slice(&[key; 32]);
+            }
+            bytes.extend_from_slice(&[9; 32]);
+            // instructions are not parsed
+            bytes.extend_from_slice(&[0xff, 0xff]);
+
+            let tx = RawTransaction::parse(&bytes).expect("valid tx");
+            assert_eq!(tx.version, version);
+            assert_eq!(
+                tx.header,
+                RawMessageHeader {
+                    num_required_signatures: 2,
+                    num_readonly_signed_accounts: 0,
+                    num_readonly_unsigned_accounts: 1,
+                }
+            );
+            assert_eq!(tx.signatures_count(), 2);
+            assert_eq!(tx.signature(1), Some(Signature::from([2; 64])));
+            assert_eq!(
+                tx.signatures().collect::<Vec<_>>(),
+                [Signature::from([1; 64]), Signature::from([2; 64])]
+            );
+            assert_eq!(tx.account_keys_count(), 3);
+            assert_eq!(tx.account_key(2), Some(&[2; 32]));
+            assert_eq!(tx.account_key(3), None);
+            assert_eq!(tx.account_keys().count(), 3);
+            assert_eq!(tx.recent_blockhash, Hash::from([9; 32]));
+            assert_eq!(
+                RawTransaction::parse_signature(&bytes),
+                Ok(Signature::from([1; 64]))
+            );
+
+            assert_eq!(
+                RawTransaction::parse(&bytes[..bytes.len() - 40]),
+                Err(RawTransactionError::UnexpectedEnd(bytes.len() - 130))
+            );
+        }
+        assert_eq!(
+            RawTransaction::parse(&[0]),
+            Err(RawTransactionError::NoSignatures)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use {
+    crate::{
+        node::{DataFrame, Nodes, ReassableError},
+        rawtx::RawTransactionError,
+        types::Signature,
+    },
+    solana_transaction_status::EncodeError,
+    std::{io, num::NonZeroUsize, thread},
+    thiserror::Error,
+};
+pub use {
+    block::{ConfirmedBlockBuilder, build_confirmed_block},
+    detect::{MetaDetection, MetaDetector, MetaFormat},
+    filter::{TransactionFilter, VOTE_PROGRAM_ID},
+    meta::{
+        BincodeMetaEra, StoredInnerInstructions, StoredReward, StoredTokenBalance,
+        StoredTransactionStatusMeta, StoredUiTokenAmount, decode_bincode_meta,
+        decode_bincode_meta_era,
+    },
+    poh::{DecodedEntry, DecodedEntryFull, PohMismatch, hash_signatures, next_hash, verify_poh},
+    rewards::DecodedRewards,
+    transaction::{AccountKeySource, DecodedTransaction, TransactionAccount},
+    verify::{BlockSignatures, SignatureFailure, SignatureVerifier, verify_signatures},
+};
+
+mod block;
+mod detect;
+mod filter;
+mod meta;
+mod poh;
+mod rewards;
+mod transaction;
+mod verify;
+
+/// Apply `f` to indices `0..len` split between `threads` scoped threads (by default the number
+/// of available CPUs), results are in the index order
+fn parallel_filter_map<T, F>(len: usize, threads: Option<NonZeroUsize>, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> Option<T> + Sync,
+{
+    let threads = threads
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+    let chunk_size = len.div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let f = &f;
+        let handles = (0..len)
+            .step_by(chunk_size)
+            .map(|first| {
+                scope.spawn(move || {
+                    (first..(first + chunk_size).min(len))
+                        .filter_map(f)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("worker thread panicked"))
+            .collect()
+    })
+}
+
+/// Reassemble and decompress zstd data, `None` if data is empty
+pub fn reassemble_decompress(
+    nodes: &Nodes,
+    dataframe: &DataFrame,
+) -> Result<Option<Vec<u8>>, DecodeError> {
+    let (buf, _checksum) = nodes.reassemble_dataframes_buf(dataframe)?;
+    if bytes::Buf::has_remaining(&buf) {
+        zstd::decode_all(buf)
+            .map(Some)
+            .map_err(DecodeError::Decompress)
+    } else {
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error(transparent)]
+    Reassable(#[from] ReassableError),
+    #[error("failed to decompress: {0}")]
+    Decompress(io::Error),
+    #[error("failed to decode transaction: {0}")]
+    Transaction(bincode::Error),
+    #[error(transparent)]
+    RawTransaction(#[from] RawTransactionError),
+    #[error("transaction without signatures")]
+    MissedSignature,
+    #[error("transaction without metadata is not legacy: {0}")]
+    MissedMetadata(Signature),
+    #[error("block without entries: {0}")]
+    MissedEntries(u64),
+    #[error("blockhash of the parent block is not known: {0}")]
+    MissedParentBlockhash(u64),
+    #[error("failed to encode: {0}")]
+    Encode(EncodeError),
+    #[error("ambiguous metadata format: {0:?}")]
+    AmbiguousMetadata(Vec<MetaFormat>),
+    #[error("unknown metadata format")]
+    UnknownMetadata,
+    #[error("ambiguous rewards format: both protobuf and bincode")]
+    AmbiguousRewards,
+    #[error("unknown rewards format")]
+    UnknownRewards,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::parallel_filter_map, std::num::NonZeroUsize};
+
+    #[test]
+    fn test_parallel_filter_map() {
+        for threads in [None, NonZeroUsize::new(1), NonZeroUsize::new(3)] {
+            for len in [0, 1, 2, 100] {
+                assert_eq!(
+                    parallel_filter_map(len, threads, |index| (index % 7 == 0).then_some(index)),
+                    (0..len).filter(|index| index % 7 == 0).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use {
+    crate::{
+        bundle::BlockBundle,
+        solana::{DecodeError, DecodedRewards, DecodedTransaction},
+        types::Hash,
+    },
+    solana_transaction_status::{
+        BlockEncodingOptions, ConfirmedBlock, TransactionWithStatusMeta, UiConfirmedBlock,
+        UiTransactionEncoding, VersionedTransactionWithStatusMeta,
+    },
+};
+
+/// Build RPC `getBlock` compatible block, blockhash of the block is the hash of the last entry
+pub fn build_confirmed_block(
+    bundle: &BlockBundle,
+    previous_blockhash: Hash,
+) -> Result<ConfirmedBlock, DecodeError> {
+    let slot = bundle.block.slot;
+    let blockhash = bundle
+        .entries
+        .last()
+        .map(|entry| entry.entry.hash)
+        .ok_or(DecodeError::MissedEntries(slot))?;
+
+    let transactions = bundle
+        .transactions()
+        .map(|(_position, _cid, tx)| {
+            let tx = DecodedTransaction::decode(tx, &bundle.dataframes)?;
+            match tx.meta {
+                Some(meta) => Ok(TransactionWithStatusMeta::Complete(
+                    VersionedTransactionWithStatusMeta {
+                        transaction: tx.tx,
+                        meta,
+                    },
+                )),
+                None => tx
+                    .tx
+                    .into_legacy_transaction()
+                    .map(TransactionWithStatusMeta::MissingMetadata)
+                    .ok_or(DecodeError::MissedMetadata(tx.signature)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (rewards, num_partitions) = match &bundle.rewards {
+        Some((_cid, rewards)) => {
+            let rewards = DecodedRewards::decode(rewards, &bundle.dataframes)?;
+            (rewards.rewards, rewards.num_partitions)
+        }
+        None => (vec![], None),
+    };
+
+    Ok(ConfirmedBlock {
+        previous_blockhash: previous_blockhash.to_string(),
+        blockhash: blockhash.to_string(),
+        parent_slot: bundle.block.meta.parent_slot,
+        transactions,
+        rewards,
+        num_partitions,
+        // blocktime is not set for the early blocks
+        block_time: match bundle.block.meta.blocktime {
+            0 => None,
+            blocktime => Some(blocktime as i64),
+        },
+        block_height: bundle.block.meta.block_height,
+    })
+}
+
+/// Builder of RPC `getBlock` compatible blocks, blocks should be pushed in the file order
+///
+/// Wrapper of [`build_confirmed_block`], previous blockhash is taken from the previously pushed
+/// block if it's the parent block, even if that block failed to decode.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConfirmedBlockBuilder {
+    parent: Option<(u64, Hash)>,
+}
+
+impl ConfirmedBlockBuilder {
+    /// Set slot and blockhash of the block before the first pushed block
+    pub const fn with_parent(mut self, slot: u64, blockhash: Hash) -> Self {
+        self.parent = Some((slot, blockhash));
+        self
+    }
+
+    pub fn push(&mut self, bundle: &BlockBundle) -> Result<ConfirmedBlock, DecodeError> {
+        let slot = bundle.block.slot;
+        let parent_slot = bundle.block.meta.parent_slot;
+
+        // remember the block even if it fails to decode, so the next block still has the parent
+        let blockhash = bundle.entries.last().map(|entry| entry.entry.hash);
+        let parent = std::mem::replace(&mut self.parent, blockhash.map(|hash| (slot, hash)));
+        if blockhash.is_none() {
+            return Err(DecodeError::MissedEntries(slot));
+        }
+        match parent {
+            Some((slot, blockhash)) if slot == parent_slot => {
+                build_confirmed_block(bundle, blockhash)
+            }
+            _ => Err(DecodeError::MissedParentBlockhash(parent_slot)),
+        }
+    }
+
+    /// Build block and encode it like RPC `getBlock`, supported encodings are `json`,
+    /// `jsonParsed`, `base58` and `base64`
+    pub fn push_encoded(
+        &mut self,
+        bundle: &BlockBundle,
+        encoding: UiTransactionEncoding,
+        options: BlockEncodingOptions,
+    ) -> Result<UiConfirmedBlock, DecodeError> {
+        self.push(bundle)?
+            .encode_with_options(encoding, options)
+            .map_err(DecodeError::Encode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{ConfirmedBlockBuilder, build_confirmed_block},
+        crate::{
+            bundle::BlockBundle,
+            node::{Block, DataFrame, Entry, Node, SlotMeta, Transaction},
+            solana::DecodeError,
+            types::Hash,
+            util::tests::{build_nodes, make_cid},
+        },
+        solana_sdk::{
+            message::{Message, VersionedMessage},
+            pubkey::Pubkey,
+            signature::Signature,
+            transaction::VersionedTransaction,
+        },
+        solana_transaction_status::{
+            BlockEncodingOptions, EncodedTransaction, TransactionDetails, UiTransactionEncoding,
+        },
+    };
+
+    fn build_bundle(slot: u64, parent_slot: u64) -> BlockBundle {
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::from([slot as u8; 64])],
+            message: VersionedMessage::Legacy(Message::new_with_blockhash(
+                &[],
+                Some(&Pubkey::new_from_array([1; 32])),
+                &Default::default(),
+            )),
+        };
+
+        let nodes = build_nodes([
+            (
+                1,
+                Node::Transaction(Transaction {
+                    data: DataFrame {
+                        data: bincode::serialize(&tx).expect("valid tx"),
+                        ..Default::default()
+                    },
+                    slot,
+                    ..Default::default()
+                }),
+            ),
+            (
+                2,
+                Node::Entry(Entry {
+                    num_hashes: 1,
+                    hash: Hash::from([slot as u8; 32]),
+                    transactions: vec![make_cid(1)],
+                }),
+            ),
+            (
+                3,
+                Node::Block(Block {
+                    slot,
+                    entries: vec![make_cid(2)],
+                    meta: SlotMeta {
+                        parent_slot,
+                        blocktime: 100,
+                        block_height: Some(50),
+                    },
+                    ..Default::default()
+                }),
+            ),
+        ]);
+        BlockBundle::try_from(nodes).expect("valid bundle")
+    }
+
+    #[test]
+    fn test_build() {
+        let mut builder = ConfirmedBlockBuilder::default().with_parent(9, Hash::from([9; 32]));
+
+        let block = builder.push(&build_bundle(10, 9)).expect("valid block");
+        assert_eq!(block.previous_blockhash, Hash::from([9; 32]).to_string());
+        assert_eq!(block.blockhash, Hash::from([10; 32]).to_string());
+        assert_eq!(block.parent_slot, 9);
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(block.block_time, Some(100));
+        assert_eq!(block.block_height, Some(50));
+
+        let block = builder
+            .push_encoded(
+                &build_bundle(12, 10),
+                UiTransactionEncoding::Base64,
+                BlockEncodingOptions {
+                    transaction_details: TransactionDetails::Full,
+                    show_rewards: true,
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .expect("valid block");
+        assert_eq!(block.previous_blockhash, Hash::from([10; 32]).to_string());
+        let transactions = block.transactions.expect("full details");
+        assert!(matches!(
+            transactions[0].transaction,
+            EncodedTransaction::Binary(_, _)
+        ));
+        assert!(transactions[0].meta.is_none());
+        assert_eq!(block.rewards, Some(vec![]));
+
+        let block = builder
+            .push_encoded(
+                &build_bundle(13, 12),
+                UiTransactionEncoding::Json,
+                BlockEncodingOptions {
+                    transaction_details: TransactionDetails::Signatures,
+                    show_rewards: false,
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .expect("valid block");
+        assert_eq!(
+            block.signatures,
+            Some(vec![Signature::from([13; 64]).to_string()])
+        );
+        assert!(block.transactions.is_none());
+
+        assert!(matches!(
+            builder.push(&build_bundle(15, 14)),
+            Err(DecodeError::MissedParentBlockhash(14))
+        ));
+    }
+
+    #[test]
+    fn test_build_stateless() {
+        let block = build_confirmed_block(&build_bundle(15, 14), Hash::from([14; 32]))
+            .expect("valid block");
+        assert_eq!(block.previous_blockhash, Hash::from([14; 32]).to_string());
+        assert_eq!(block.blockhash, Hash::from([15; 32]).to_string());
+        assert_eq!(block.parent_slot, 14);
+
+        let mut bundle = build_bundle(16, 15);
+        bundle.entries.clear();
+        assert!(matches!(
+            build_confirmed_block(&bundle, Hash::default()),
+            Err(DecodeError::MissedEntries(16))
+        ));
+    }
+
+    #[test]
+    fn test_push_after_error() {
+        let mut builder = ConfirmedBlockBuilder::default().with_parent(9, Hash::from([9; 32]));
+
+        let mut bundle = build_bundle(10, 9);
+        bundle.entries[0].transactions[0].1.data.data = vec![1, 2, 3];
+        assert!(matches!(
+            builder.push(&bundle),
+            Err(DecodeError::Transaction(_))
+        ));
+        let block = builder.push(&build_bundle(11, 10)).expect("valid block");
+        assert_eq!(block.previous_blockhash, Hash::from([10; 32]).to_string());
+
+        assert!(matches!(
+            builder.push(&build_bundle(13, 12)),
+            Err(DecodeError::MissedParentBlockhash(12))
+        ));
+        let mut bundle = build_bundle(14, 13);
+        bundle.block.meta.blocktime = 0;
+        let block = builder.push(&bundle).expect("valid block");
+        assert_eq!(block.previous_blockhash, Hash::from([13; 32]).to_string());
+        assert_eq!(block.block_time, None);
+    }
+}
@@ -0,0 +1,227 @@
+use {
+    crate::solana::{BincodeMetaEra, decode_bincode_meta},
+    prost::Message,
+    solana_storage_proto::convert::generated,
+    solana_transaction_status::TransactionStatusMeta,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetaFormat {
+    Protobuf,
+    Bincode(BincodeMetaEra),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetaDetection {
+    Detected {
+        format: MetaFormat,
+        meta: Box<TransactionStatusMeta>,
+    },
+    /// More than one format passed all checks
+    Ambiguous(Vec<(MetaFormat, TransactionStatusMeta)>),
+    /// No format passed all checks
+    Unknown,
+}
+
+/// Detector of the metadata encoding
+///
+/// Instead of trying protobuf first, every format is decoded and checked: format should be
+/// allowed for the slot, all bytes should be consumed and the decoded metadata should match
+/// the transaction.
+///
+/// Default is [`MetaDetector::ANY`], slot bounds are opt-in.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetaDetector {
+    /// Protobuf is not possible before this slot
+    pub protobuf_first_slot: Option<u64>,
+    /// Bincode is not possible after this slot
+    pub bincode_last_slot: Option<u64>,
+}
+
+impl MetaDetector {
+    /// Every format is allowed for every slot
+    pub const ANY: Self = Self {
+        protobuf_first_slot: None,
+        bincode_last_slot: None,
+    };
+
+    /// Conservative bounds of the mainnet-beta switch of blockstore metadata from bincode to
+    /// protobuf: protobuf is not possible before epoch 100 and bincode is not possible after
+    /// epoch 299
+    ///
+    /// The bounds are not taken from a published source, they are a wide estimate around the
+    /// validator releases that started to write protobuf metadata (validators switched one by
+    /// one, so both formats are allowed in between). Check them against the archives in use
+    /// before opting in, a wrong bound turns valid metadata into [`MetaDetection::Unknown`].
+    pub const MAINNET: Self = Self {
+        protobuf_first_slot: Some(100 * 432_000),
+        bincode_last_slot: Some(300 * 432_000 - 1),
+    };
+
+    /// `account_keys` is the number of static account keys in the transaction message
+    pub fn detect(&self, bytes: &[u8], slot: u64, account_keys: Option<usize>) -> MetaDetection {
+        let mut candidates = vec![];
+
+        if self.protobuf_first_slot.is_none_or(|first| slot >= first) {
+            if let Some(meta) = Self::decode_protobuf(bytes) {
+                candidates.push((MetaFormat::Protobuf, meta));
+            }
+        }
+
+        if self.bincode_last_slot.is_none_or(|last| slot <= last) {
+            if let Ok((era, meta)) = decode_bincode_meta(bytes) {
+                candidates.push((MetaFormat::Bincode(era), meta));
+            }
+        }
+
+        candidates.retain(|(_format, meta)| Self::is_consistent(meta, account_keys));
+        match candidates.len() {
+            0 => MetaDetection::Unknown,
+            1 => {
+                let (format, meta) = candidates.pop().expect("one candidate");
+                MetaDetection::Detected {
+                    format,
+                    meta: Box::new(meta),
+                }
+            }
+            _ => MetaDetection::Ambiguous(candidates),
+        }
+    }
+
+    fn decode_protobuf(bytes: &[u8]) -> Option<TransactionStatusMeta> {
+        let meta = generated::TransactionStatusMeta::decode(bytes).ok()?;
+        // prost skips unknown fields, re-encoded message is shorter for garbage input
+        if meta.encoded_len() != bytes.len() {
+            return None;
+        }
+        TransactionStatusMeta::try_from(meta).ok()
+    }
+
+    fn is_consistent(meta: &TransactionStatusMeta, account_keys: Option<usize>) -> bool {
+        if meta.pre_balances.len() != meta.post_balances.len() {
+            return false;
+        }
+
+        let Some(account_keys) = account_keys else {
+            return true;
+        };
+        let account_keys = account_keys + meta.loaded_addresses.len();
+        if meta.pre_balances.len() != account_keys {
+            return false;
+        }
+
+        let token_balances = meta
+            .pre_token_balances
+            .iter()
+            .chain(meta.post_token_balances.iter())
+            .flatten();
+        for balance in token_balances {
+            if balance.account_index as usize >= account_keys {
+                return false;
+            }
+        }
+
+        let instructions = meta
+            .inner_instructions
+            .iter()
+            .flatten()
+            .flat_map(|inner| inner.instructions.iter());
+        for inner in instructions {
+            let instruction = &inner.instruction;
+            if instruction.program_id_index as usize >= account_keys
+                || instruction
+                    .accounts
+                    .iter()
+                    .any(|index| *index as usize >= account_keys)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{MetaDetection, MetaDetector, MetaFormat},
+        crate::solana::{BincodeMetaEra, StoredTransactionStatusMeta},
+        prost::Message,
+        solana_storage_proto::convert::generated,
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    #[test]
+    fn test_detect() {
+        let meta = TransactionStatusMeta {
+            fee: 5000,
+            pre_balances: vec![10, 20],
+            post_balances: vec![5, 20],
+            ..Default::default()
+        };
+        let protobuf = generated::TransactionStatusMeta::from(meta.clone()).encode_to_vec();
+        let bincode = bincode::serialize(&StoredTransactionStatusMeta {
+            err: Ok(()),
+            fee: 5000,
+            pre_balances: vec![10, 20],
+            post_balances: vec![5, 20],
+        })
+        .expect("valid meta");
+
+        let detector = MetaDetector::default();
+        for (bytes, expected_format) in [
+            (&protobuf, MetaFormat::Protobuf),
+            (&bincode, MetaFormat::Bincode(BincodeMetaEra::Status)),
+        ] {
+            let MetaDetection::Detected { format, meta } = detector.detect(bytes, 1, Some(2))
+            else {
+                panic!("expected detected format");
+            };
+            assert_eq!(format, expected_format);
+            assert_eq!(meta.fee, 5000);
+            assert_eq!(meta.pre_balances, [10, 20]);
+            assert_eq!(meta.post_balances, [5, 20]);
+        }
+        assert_eq!(
+            detector.detect(&bincode, 1, Some(3)),
+            MetaDetection::Unknown
+        );
+
+        // trailing garbage is skipped by prost as unknown field
+        let mut garbage = protobuf.clone();
+        garbage.extend_from_slice(&[0xf8, 0x01, 0x00]);
+        assert_eq!(detector.detect(&garbage, 1, None), MetaDetection::Unknown);
+
+        let detector = MetaDetector {
+            protobuf_first_slot: Some(100),
+            bincode_last_slot: Some(99),
+        };
+        assert_eq!(detector.detect(&protobuf, 99, None), MetaDetection::Unknown);
+        assert_eq!(detector.detect(&bincode, 100, None), MetaDetection::Unknown);
+
+        let detector = MetaDetector::MAINNET;
+        assert!(matches!(
+            detector.detect(&bincode, 1, Some(2)),
+            MetaDetection::Detected {
+                format: MetaFormat::Bincode(_),
+                ..
+            }
+        ));
+        assert_eq!(
+            detector.detect(&protobuf, 1, Some(2)),
+            MetaDetection::Unknown
+        );
+        assert!(matches!(
+            detector.detect(&protobuf, 300 * 432_000, Some(2)),
+            MetaDetection::Detected {
+                format: MetaFormat::Protobuf,
+                ..
+            }
+        ));
+        assert_eq!(
+            detector.detect(&bincode, 300 * 432_000, Some(2)),
+            MetaDetection::Unknown
+        );
+    }
+}
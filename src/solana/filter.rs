@@ -0,0 +1,252 @@
+use {
+    crate::{
+        node::{Nodes, Transaction},
+        rawtx::RawTransaction,
+        solana::{DecodeError, DecodedTransaction, MetaDetector},
+    },
+    solana_sdk::pubkey::Pubkey,
+    std::collections::HashSet,
+};
+
+pub const VOTE_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("Vote111111111111111111111111111111111111111");
+
+/// Filter of transactions, all set conditions should match
+///
+/// Checks on static account keys are done on raw transaction bytes, so transactions which can not
+/// match are skipped without full deserialization and metadata decompression.
+#[derive(Debug, Default, Clone)]
+pub struct TransactionFilter {
+    /// Any of accounts, including loaded addresses
+    pub accounts: HashSet<Pubkey>,
+    /// Any of invoked programs, including inner instructions
+    pub programs: HashSet<Pubkey>,
+    /// Transactions without metadata do not match if set
+    pub success: Option<bool>,
+    /// Vote transaction invokes the vote program in top-level instructions
+    pub vote: Option<bool>,
+    pub detector: MetaDetector,
+}
+
+impl TransactionFilter {
+    /// Decode transaction only if it matches the filter
+    pub fn filter(
+        &self,
+        transaction: &Transaction,
+        nodes: &Nodes,
+    ) -> Result<Option<DecodedTransaction>, DecodeError> {
+        let payload = transaction.payload(nodes)?;
+        if !self.may_match(&RawTransaction::parse(&payload)?) {
+            return Ok(None);
+        }
+
+        let tx = DecodedTransaction::decode_payload(&payload, transaction, nodes, &self.detector)?;
+        Ok(self.matches(&tx).then_some(tx))
+    }
+
+    /// Cheap check on static account keys, `false` if transaction can not match the filter
+    pub fn may_match(&self, raw: &RawTransaction) -> bool {
+        let mut has_vote_program = false;
+        let mut has_account = false;
+        let mut has_program = false;
+        for key in raw.account_keys() {
+            let key = Pubkey::new_from_array(*key);
+            has_vote_program |= key == VOTE_PROGRAM_ID;
+            has_account |= self.accounts.contains(&key);
+            has_program |= self.programs.contains(&key);
+        }
+
+        // legacy transactions do not have loaded addresses
+        let has_loaded = raw.version.is_some();
+        (self.vote != Some(true) || has_vote_program)
+            && (self.accounts.is_empty() || has_account || has_loaded)
+            && (self.programs.is_empty() || has_program || has_loaded)
+    }
+
+    pub fn matches(&self, tx: &DecodedTransaction) -> bool {
+        if let Some(success) = self.success {
+            match &tx.meta {
+                Some(meta) if meta.status.is_ok() == success => {}
+                _ => return false,
+            }
+        }
+
+        let static_keys = tx.tx.message.static_account_keys();
+        if let Some(vote) = self.vote {
+            let is_vote = tx.tx.message.instructions().iter().any(|instruction| {
+                static_keys.get(instruction.program_id_index as usize) == Some(&VOTE_PROGRAM_ID)
+            });
+            if is_vote != vote {
+                return false;
+            }
+        }
+
+        if self.accounts.is_empty() && self.programs.is_empty() {
+            return true;
+        }
+        let account_keys = tx.account_keys();
+
+        if !self.accounts.is_empty() && !account_keys.iter().any(|key| self.accounts.contains(key))
+        {
+            return false;
+        }
+
+        if !self.programs.is_empty() {
+            let inner_instructions = tx
+                .meta
+                .iter()
+                .flat_map(|meta| meta.inner_instructions.iter().flatten())
+                .flat_map(|inner| inner.instructions.iter())
+                .map(|inner| &inner.instruction);
+            let mut invoked = tx
+                .tx
+                .message
+                .instructions()
+                .iter()
+                .chain(inner_instructions)
+                .filter_map(|instruction| account_keys.get(instruction.program_id_index as usize));
+            if !invoked.any(|key| self.programs.contains(key)) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{TransactionFilter, VOTE_PROGRAM_ID},
+        crate::{
+            node::{DataFrame, Nodes, Transaction},
+            rawtx::RawTransaction,
+            solana::DecodedTransaction,
+        },
+        solana_sdk::{
+            instruction::CompiledInstruction,
+            message::{Message, MessageHeader, VersionedMessage},
+            pubkey::Pubkey,
+            signature::Signature,
+            transaction::{TransactionError, VersionedTransaction},
+        },
+        solana_transaction_status::{InnerInstruction, InnerInstructions, TransactionStatusMeta},
+        std::collections::HashSet,
+    };
+
+    fn build_tx(program: Pubkey, meta: Option<TransactionStatusMeta>) -> DecodedTransaction {
+        DecodedTransaction {
+            signature: [1; 64].into(),
+            slot: 1,
+            index: None,
+            tx: VersionedTransaction {
+                signatures: vec![Signature::from([1; 64])],
+                message: VersionedMessage::Legacy(Message {
+                    header: MessageHeader {
+                        num_required_signatures: 1,
+                        num_readonly_signed_accounts: 0,
+                        num_readonly_unsigned_accounts: 2,
+                    },
+                    account_keys: vec![
+                        Pubkey::new_from_array([1; 32]),
+                        program,
+                        Pubkey::new_from_array([3; 32]),
+                    ],
+                    instructions: vec![CompiledInstruction::new_from_raw_parts(1, vec![], vec![0])],
+                    ..Default::default()
+                }),
+            },
+            meta,
+        }
+    }
+
+    fn build_filter(
+        accounts: &[u8],
+        programs: &[u8],
+        success: Option<bool>,
+        vote: Option<bool>,
+    ) -> TransactionFilter {
+        let keys = |seeds: &[u8]| {
+            seeds
+                .iter()
+                .map(|seed| Pubkey::new_from_array([*seed; 32]))
+                .collect::<HashSet<_>>()
+        };
+        TransactionFilter {
+            accounts: keys(accounts),
+            programs: keys(programs),
+            success,
+            vote,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let meta = TransactionStatusMeta {
+            status: Err(TransactionError::AccountNotFound),
+            inner_instructions: Some(vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(2, vec![], vec![]),
+                    stack_height: Some(2),
+                }],
+            }]),
+            ..Default::default()
+        };
+        let tx = build_tx(Pubkey::new_from_array([2; 32]), Some(meta));
+        for (filter, matches) in [
+            (build_filter(&[], &[], None, None), true),
+            (build_filter(&[1], &[], None, None), true),
+            (build_filter(&[4], &[], None, None), false),
+            (build_filter(&[], &[2], None, None), true),
+            // invoked by inner instruction
+            (build_filter(&[], &[3], None, None), true),
+            (build_filter(&[], &[1], None, None), false),
+            (build_filter(&[], &[], Some(false), None), true),
+            (build_filter(&[], &[], Some(true), None), false),
+            (build_filter(&[], &[], None, Some(false)), true),
+            (build_filter(&[], &[], None, Some(true)), false),
+        ] {
+            assert_eq!(filter.matches(&tx), matches, "{filter:?}");
+        }
+
+        let vote = build_tx(VOTE_PROGRAM_ID, None);
+        assert!(build_filter(&[], &[], None, Some(true)).matches(&vote));
+        assert!(!build_filter(&[], &[], Some(true), None).matches(&vote));
+    }
+
+    #[test]
+    fn test_filter() {
+        let tx = build_tx(Pubkey::new_from_array([2; 32]), None);
+        let payload = bincode::serialize(&tx.tx).expect("valid tx");
+        let raw = RawTransaction::parse(&payload).expect("valid tx");
+        assert!(build_filter(&[3], &[], None, None).may_match(&raw));
+        assert!(!build_filter(&[4], &[], None, None).may_match(&raw));
+        assert!(!build_filter(&[], &[4], None, None).may_match(&raw));
+        assert!(!build_filter(&[], &[], None, Some(true)).may_match(&raw));
+
+        let transaction = Transaction {
+            data: DataFrame {
+                data: payload,
+                ..Default::default()
+            },
+            // invalid metadata, decoded only if pre-filter passed
+            metadata: DataFrame {
+                data: vec![1, 2, 3],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let nodes = Nodes::default();
+        assert!(matches!(
+            build_filter(&[4], &[], None, None).filter(&transaction, &nodes),
+            Ok(None)
+        ));
+        assert!(
+            build_filter(&[3], &[], None, None)
+                .filter(&transaction, &nodes)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use {
+    bincode::Options,
+    serde::{Deserialize, Serialize, de::DeserializeOwned},
+    solana_sdk::{instruction::CompiledInstruction, transaction::TransactionError},
+    solana_storage_proto::StoredTokenAmount,
+    solana_transaction_status::{
+        InnerInstruction, InnerInstructions, Reward, RewardType, TransactionStatusMeta,
+        TransactionTokenBalance,
+    },
+};
+
+/// Layouts of bincode encoded metadata, every era appends fields to the previous one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BincodeMetaEra {
+    /// `status`, `fee`, `pre_balances`, `post_balances`
+    Status,
+    /// `inner_instructions` added
+    InnerInstructions,
+    /// `log_messages` added
+    LogMessages,
+    /// `pre_token_balances` and `post_token_balances` added
+    TokenBalances,
+    /// `rewards` added
+    Rewards,
+}
+
+impl BincodeMetaEra {
+    /// From the latest to the earliest
+    pub const ALL: [Self; 5] = [
+        Self::Rewards,
+        Self::TokenBalances,
+        Self::LogMessages,
+        Self::InnerInstructions,
+        Self::Status,
+    ];
+}
+
+/// Bincode encoded metadata from the early epochs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredTransactionStatusMeta {
+    pub err: Result<(), TransactionError>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+}
+
+impl From<StoredTransactionStatusMeta> for TransactionStatusMeta {
+    fn from(value: StoredTransactionStatusMeta) -> Self {
+        Self {
+            status: value.err,
+            fee: value.fee,
+            pre_balances: value.pre_balances,
+            post_balances: value.post_balances,
+            ..Default::default()
+        }
+    }
+}
+
+/// Inner instructions before `stack_height` was added
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredInnerInstructions {
+    pub index: u8,
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+impl From<StoredInnerInstructions> for InnerInstructions {
+    fn from(value: StoredInnerInstructions) -> Self {
+        Self {
+            index: value.index,
+            instructions: value
+                .instructions
+                .into_iter()
+                .map(|instruction| InnerInstruction {
+                    instruction,
+                    stack_height: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Token amount with the layout of [`StoredTokenAmount`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredUiTokenAmount {
+    pub ui_amount: f64,
+    pub decimals: u8,
+    pub amount: String,
+}
+
+impl From<StoredUiTokenAmount> for StoredTokenAmount {
+    fn from(value: StoredUiTokenAmount) -> Self {
+        Self {
+            ui_amount: value.ui_amount,
+            decimals: value.decimals,
+            amount: value.amount,
+        }
+    }
+}
+
+/// Token balance before `owner` and `program_id` were added
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredTokenBalance {
+    pub account_index: u8,
+    pub mint: String,
+    pub ui_token_amount: StoredUiTokenAmount,
+}
+
+impl From<StoredTokenBalance> for TransactionTokenBalance {
+    fn from(value: StoredTokenBalance) -> Self {
+        Self {
+            account_index: value.account_index,
+            mint: value.mint,
+            ui_token_amount: StoredTokenAmount::from(value.ui_token_amount).into(),
+            owner: String::new(),
+            program_id: String::new(),
+        }
+    }
+}
+
+/// Reward before `commission` was added
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredReward {
+    pub pubkey: String,
+    pub lamports: i64,
+    pub post_balance: u64,
+    pub reward_type: Option<RewardType>,
+}
+
+impl From<StoredReward> for Reward {
+    fn from(value: StoredReward) -> Self {
+        Self {
+            pubkey: value.pubkey,
+            lamports: value.lamports,
+            post_balance: value.post_balance,
+            reward_type: value.reward_type,
+            commission: None,
+        }
+    }
+}
+
+// bincode has no framing, so nested tuples have the same layout as a flat struct
+type LayoutInnerInstructions = (
+    StoredTransactionStatusMeta,
+    Option<Vec<StoredInnerInstructions>>,
+);
+type LayoutLogMessages = (LayoutInnerInstructions, Option<Vec<String>>);
+type LayoutTokenBalances = (
+    LayoutLogMessages,
+    Option<Vec<StoredTokenBalance>>,
+    Option<Vec<StoredTokenBalance>>,
+);
+type LayoutRewards = (LayoutTokenBalances, Option<Vec<StoredReward>>);
+
+fn convert_vec<T, U: From<T>>(value: Option<Vec<T>>) -> Option<Vec<U>> {
+    value.map(|items| items.into_iter().map(Into::into).collect())
+}
+
+pub(crate) fn deserialize_exact<T: DeserializeOwned>(bytes: &[u8]) -> bincode::Result<T> {
+    bincode::options()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+        .deserialize(bytes)
+}
+
+/// Decode metadata with the layout of the given era, all bytes should be consumed
+pub fn decode_bincode_meta_era(
+    bytes: &[u8],
+    era: BincodeMetaEra,
+) -> bincode::Result<TransactionStatusMeta> {
+    let (base, inner_instructions, log_messages, pre_token_balances, post_token_balances, rewards) =
+        match era {
+            BincodeMetaEra::Status => {
+                let base = deserialize_exact::<StoredTransactionStatusMeta>(bytes)?;
+                (base, None, None, None, None, None)
+            }
+            BincodeMetaEra::InnerInstructions => {
+                let (base, inner) = deserialize_exact::<LayoutInnerInstructions>(bytes)?;
+                (base, inner, None, None, None, None)
+            }
+            BincodeMetaEra::LogMessages => {
+                let ((base, inner), logs) = deserialize_exact::<LayoutLogMessages>(bytes)?;
+                (base, inner, logs, None, None, None)
+            }
+            BincodeMetaEra::TokenBalances => {
+                let (((base, inner), logs), pre, post) =
+                    deserialize_exact::<LayoutTokenBalances>(bytes)?;
+                (base, inner, logs, pre, post, None)
+            }
+            BincodeMetaEra::Rewards => {
+                let ((((base, inner), logs), pre, post), rewards) =
+                    deserialize_exact::<LayoutRewards>(bytes)?;
+                (base, inner, logs, pre, post, rewards)
+            }
+        };
+    Ok(TransactionStatusMeta {
+        inner_instructions: convert_vec(inner_instructions),
+        log_messages,
+        pre_token_balances: convert_vec(pre_token_balances),
+        post_token_balances: convert_vec(post_token_balances),
+        rewards: convert_vec(rewards),
+        ..base.into()
+    })
+}
+
+/// Decode metadata trying layouts from the latest to the earliest, returns the matched era
+///
+/// Error of the earliest layout is returned if nothing matched.
+pub fn decode_bincode_meta(
+    bytes: &[u8],
+) -> bincode::Result<(BincodeMetaEra, TransactionStatusMeta)> {
+    let mut error = None;
+    for era in BincodeMetaEra::ALL {
+        match decode_bincode_meta_era(bytes, era) {
+            Ok(meta) => return Ok((era, meta)),
+            Err(err) => error = Some(err),
+        }
+    }
+    Err(error.expect("at least one era"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            BincodeMetaEra, StoredInnerInstructions, StoredReward, StoredTokenBalance,
+            StoredTransactionStatusMeta, StoredUiTokenAmount, decode_bincode_meta,
+        },
+        solana_sdk::{instruction::CompiledInstruction, transaction::TransactionError},
+        solana_transaction_status::{InnerInstruction, InnerInstructions, Reward, RewardType},
+    };
+
+    #[test]
+    fn test_decode_eras() {
+        let status = StoredTransactionStatusMeta {
+            err: Err(TransactionError::AccountNotFound),
+            fee: 5000,
+            pre_balances: vec![10, 20],
+            post_balances: vec![5, 20],
+        };
+        let inner = Some(vec![StoredInnerInstructions {
+            index: 1,
+            instructions: vec![CompiledInstruction::new_from_raw_parts(
+                2,
+                vec![3],
+                vec![0, 1],
+            )],
+        }]);
+        let logs = Some(vec!["Program log: hello".to_owned()]);
+        let token_balance = |amount: &str| StoredTokenBalance {
+            account_index: 1,
+            mint: "So11111111111111111111111111111111111111112".to_owned(),
+            ui_token_amount: StoredUiTokenAmount {
+                ui_amount: amount.parse::<f64>().expect("valid amount") / 100.0,
+                decimals: 2,
+                amount: amount.to_owned(),
+            },
+        };
+        let pre_token_balances = Some(vec![token_balance("150")]);
+        let post_token_balances = Some(vec![token_balance("100")]);
+        let rewards = Some(vec![StoredReward {
+            pubkey: "11111111111111111111111111111111".to_owned(),
+            lamports: 7,
+            post_balance: 17,
+            reward_type: Some(RewardType::Rent),
+        }]);
+
+        for (bytes, era) in [
+            (bincode::serialize(&status), BincodeMetaEra::Status),
+            (
+                bincode::serialize(&(&status, &inner)),
+                BincodeMetaEra::InnerInstructions,
+            ),
+            (
+                bincode::serialize(&((&status, &inner), &logs)),
+                BincodeMetaEra::LogMessages,
+            ),
+            (
+                bincode::serialize(&(
+                    ((&status, &inner), &logs),
+                    &pre_token_balances,
+                    &post_token_balances,
+                )),
+                BincodeMetaEra::TokenBalances,
+            ),
+            (
+                bincode::serialize(&(
+                    (
+                        ((&status, &inner), &logs),
+                        &pre_token_balances,
+                        &post_token_balances,
+                    ),
+                    &rewards,
+                )),
+                BincodeMetaEra::Rewards,
+            ),
+        ] {
+            let (decoded_era, meta) =
+                decode_bincode_meta(&bytes.expect("valid meta")).expect("valid meta");
+            assert_eq!(decoded_era, era);
+            assert_eq!(meta.status, status.err);
+            assert_eq!(meta.fee, 5000);
+            assert_eq!(meta.pre_balances, [10, 20]);
+            assert_eq!(meta.post_balances, [5, 20]);
+            if era >= BincodeMetaEra::InnerInstructions {
+                assert_eq!(
+                    meta.inner_instructions,
+                    Some(vec![InnerInstructions {
+                        index: 1,
+                        instructions: vec![InnerInstruction {
+                            instruction: CompiledInstruction::new_from_raw_parts(
+                                2,
+                                vec![3],
+                                vec![0, 1]
+                            ),
+                            stack_height: None,
+                        }],
+                    }])
+                );
+            }
+            if era >= BincodeMetaEra::LogMessages {
+                assert_eq!(meta.log_messages, logs);
+            }
+            if era >= BincodeMetaEra::TokenBalances {
+                for (balances, amount, ui_amount, ui_amount_string) in [
+                    (&meta.pre_token_balances, "150", 1.5, "1.5"),
+                    (&meta.post_token_balances, "100", 1.0, "1"),
+                ] {
+                    let [balance] = balances.as_deref().expect("token balances") else {
+                        panic!("expected one token balance: {balances:?}");
+                    };
+                    assert_eq!(balance.account_index, 1);
+                    assert_eq!(balance.mint, "So11111111111111111111111111111111111111112");
+                    assert_eq!(balance.ui_token_amount.ui_amount, Some(ui_amount));
+                    assert_eq!(balance.ui_token_amount.decimals, 2);
+                    assert_eq!(balance.ui_token_amount.amount, amount);
+                    assert_eq!(balance.ui_token_amount.ui_amount_string, ui_amount_string);
+                    assert_eq!(balance.owner, "");
+                    assert_eq!(balance.program_id, "");
+                }
+            } else {
+                assert_eq!(meta.pre_token_balances, None);
+                assert_eq!(meta.post_token_balances, None);
+            }
+            if era >= BincodeMetaEra::Rewards {
+                assert_eq!(
+                    meta.rewards,
+                    Some(vec![Reward {
+                        pubkey: "11111111111111111111111111111111".to_owned(),
+                        lamports: 7,
+                        post_balance: 17,
+                        reward_type: Some(RewardType::Rent),
+                        commission: None,
+                    }])
+                );
+            }
+        }
+
+        assert!(decode_bincode_meta(&[1, 2, 3]).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use {
+    crate::{
+        bundle::BlockBundle,
+        rawtx::RawTransaction,
+        solana::{DecodeError, parallel_filter_map},
+        types::{Hash, Signature},
+    },
+    solana_sdk::{
+        hash::{hash, hashv},
+        transaction::VersionedTransaction,
+    },
+    std::num::NonZeroUsize,
+};
+
+const MERKLE_LEAF_PREFIX: &[u8] = &[0];
+const MERKLE_INTERMEDIATE_PREFIX: &[u8] = &[1];
+
+/// Entry with signatures of all transactions, enough to verify PoH
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedEntry {
+    pub num_hashes: u64,
+    pub hash: Hash,
+    /// All signatures of transactions in the entry order
+    pub signatures: Vec<Signature>,
+}
+
+impl DecodedEntry {
+    /// Decode entries of the block in the block order
+    ///
+    /// Signatures are parsed from raw transaction bytes, metadata is not decoded.
+    pub fn decode_bundle(bundle: &BlockBundle) -> Result<Vec<Self>, DecodeError> {
+        bundle
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut signatures = vec![];
+                for (_cid, tx) in entry.transactions.iter() {
+                    let payload = tx.payload(&bundle.dataframes)?;
+                    signatures.extend(RawTransaction::parse(&payload)?.signatures());
+                }
+                Ok(Self {
+                    num_hashes: entry.entry.num_hashes,
+                    hash: entry.entry.hash,
+                    signatures,
+                })
+            })
+            .collect()
+    }
+
+    /// Decode entries of the block in the block order with decoded transactions
+    ///
+    /// Transactions are decoded with bincode, metadata is not decoded. Use
+    /// [`DecodedEntry::decode_bundle`] if only PoH is verified.
+    pub fn decode_bundle_full(bundle: &BlockBundle) -> Result<Vec<DecodedEntryFull>, DecodeError> {
+        bundle
+            .entries
+            .iter()
+            .map(|entry| {
+                let transactions = entry
+                    .transactions
+                    .iter()
+                    .map(|(_cid, tx)| {
+                        let payload = tx.payload(&bundle.dataframes)?;
+                        bincode::deserialize(&payload).map_err(DecodeError::Transaction)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(DecodedEntryFull {
+                    num_hashes: entry.entry.num_hashes,
+                    hash: entry.entry.hash,
+                    transactions,
+                })
+            })
+            .collect()
+    }
+
+    /// Hash of the entry computed from the previous entry hash
+    pub fn compute_hash(&self, start_hash: &Hash) -> Hash {
+        next_hash(start_hash, self.num_hashes, &self.signatures)
+    }
+}
+
+/// Entry with decoded transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedEntryFull {
+    pub num_hashes: u64,
+    pub hash: Hash,
+    /// Transactions in the entry order
+    pub transactions: Vec<VersionedTransaction>,
+}
+
+impl From<&DecodedEntryFull> for DecodedEntry {
+    fn from(entry: &DecodedEntryFull) -> Self {
+        Self {
+            num_hashes: entry.num_hashes,
+            hash: entry.hash,
+            signatures: entry
+                .transactions
+                .iter()
+                .flat_map(|tx| tx.signatures.iter().map(|signature| (*signature).into()))
+                .collect(),
+        }
+    }
+}
+
+/// Merkle root of signatures of all transactions in the entry, default hash if there are no
+/// transactions
+pub fn hash_signatures(signatures: &[Signature]) -> Hash {
+    let mut level = signatures
+        .iter()
+        .map(|signature| hashv(&[MERKLE_LEAF_PREFIX, signature.as_ref()]))
+        .collect::<Vec<_>>();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let left = pair[0].as_ref();
+                let right = pair.get(1).unwrap_or(&pair[0]).as_ref();
+                hashv(&[MERKLE_INTERMEDIATE_PREFIX, left, right])
+            })
+            .collect();
+    }
+    level.pop().map(Into::into).unwrap_or_default()
+}
+
+/// Hash `num_hashes` times from `start_hash`, the last hash mixes in signatures
+pub fn next_hash(start_hash: &Hash, num_hashes: u64, signatures: &[Signature]) -> Hash {
+    if num_hashes == 0 && signatures.is_empty() {
+        return *start_hash;
+    }
+
+    let mut current = solana_sdk::hash::Hash::from(*start_hash);
+    for _ in 1..num_hashes {
+        current = hash(current.as_ref());
+    }
+    if signatures.is_empty() {
+        hash(current.as_ref()).into()
+    } else {
+        let mixin = hash_signatures(signatures);
+        hashv(&[current.as_ref(), mixin.as_ref()]).into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PohMismatch {
+    /// Index of the entry
+    pub index: usize,
+    pub expected: Hash,
+    pub computed: Hash,
+}
+
+/// Verify PoH chain of entries, `start_hash` is the hash of the last entry of the previous block
+///
+/// Every entry is verified independently, entries are split between `threads` threads (by
+/// default the number of available CPUs).
+pub fn verify_poh(
+    start_hash: &Hash,
+    entries: &[DecodedEntry],
+    threads: Option<NonZeroUsize>,
+) -> Vec<PohMismatch> {
+    parallel_filter_map(entries.len(), threads, |index| {
+        let start_hash = match index {
+            0 => start_hash,
+            _ => &entries[index - 1].hash,
+        };
+        let entry = &entries[index];
+        let computed = entry.compute_hash(start_hash);
+        (computed != entry.hash).then_some(PohMismatch {
+            index,
+            expected: entry.hash,
+            computed,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            DecodedEntry, DecodedEntryFull, PohMismatch, hash_signatures, next_hash, verify_poh,
+        },
+        crate::{
+            bundle::BlockBundle,
+            node::{Block, DataFrame, Entry, Node, Transaction},
+            types::{Hash, Signature},
+            util::tests::{build_nodes, make_cid},
+        },
+        solana_sdk::{
+            hash::{hash, hashv},
+            message::{Message, VersionedMessage},
+            pubkey::Pubkey,
+            transaction::VersionedTransaction,
+        },
+        std::num::NonZeroUsize,
+    };
+
+    #[test]
+    fn test_decode_bundle() {
+        let tx = VersionedTransaction {
+            signatures: vec![[1; 64].into(), [2; 64].into()],
+            message: VersionedMessage::Legacy(Message::new_with_blockhash(
+                &[],
+                Some(&Pubkey::new_from_array([1; 32])),
+                &Default::default(),
+            )),
+        };
+        let nodes = build_nodes([
+            (
+                1,
+                Node::Transaction(Transaction {
+                    data: DataFrame {
+                        data: bincode::serialize(&tx).expect("valid tx"),
+                        ..Default::default()
+                    },
+                    // metadata is not decoded
+                    metadata: DataFrame {
+                        data: vec![1, 2, 3],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+            ),
+            (
+                2,
+                Node::Entry(Entry {
+                    num_hashes: 1,
+                    hash: Hash::from([2; 32]),
+                    transactions: vec![make_cid(1)],
+                }),
+            ),
+            (3, Node::Entry(Entry::default())),
+            (
+                4,
+                Node::Block(Block {
+                    entries: vec![make_cid(2), make_cid(3)],
+                    ..Default::default()
+                }),
+            ),
+        ]);
+        let bundle = BlockBundle::try_from(nodes).expect("valid bundle");
+
+        let full = DecodedEntry::decode_bundle_full(&bundle).expect("valid entries");
+        assert_eq!(
+            full,
+            [
+                DecodedEntryFull {
+                    num_hashes: 1,
+                    hash: Hash::from([2; 32]),
+                    transactions: vec![tx],
+                },
+                DecodedEntryFull {
+                    num_hashes: 0,
+                    hash: Hash::default(),
+                    transactions: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            DecodedEntry::decode_bundle(&bundle).expect("valid entries"),
+            full.iter().map(DecodedEntry::from).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            DecodedEntry::decode_bundle(&bundle).expect("valid entries"),
+            [
+                DecodedEntry {
+                    num_hashes: 1,
+                    hash: Hash::from([2; 32]),
+                    signatures: vec![Signature::from([1; 64]), Signature::from([2; 64])],
+                },
+                DecodedEntry {
+                    num_hashes: 0,
+                    hash: Hash::default(),
+                    signatures: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_signatures() {
+        let signatures = [[1; 64], [2; 64], [3; 64]].map(Signature::from);
+        let leafs = signatures.map(|signature| hashv(&[&[0], signature.as_ref()]));
+        let left = hashv(&[&[1], leafs[0].as_ref(), leafs[1].as_ref()]);
+        let right = hashv(&[&[1], leafs[2].as_ref(), leafs[2].as_ref()]);
+        let root = hashv(&[&[1], left.as_ref(), right.as_ref()]);
+
+        assert_eq!(hash_signatures(&[]), Hash::default());
+        assert_eq!(hash_signatures(&signatures[..1]), leafs[0].into());
+        assert_eq!(hash_signatures(&signatures), root.into());
+    }
+
+    #[test]
+    fn test_verify_poh() {
+        let start = Hash::from([7; 32]);
+        let tick = Hash::from(hash(hash(start.as_ref()).as_ref()));
+        assert_eq!(next_hash(&start, 0, &[]), start);
+        assert_eq!(next_hash(&start, 2, &[]), tick);
+
+        let signature = Signature::from([1; 64]);
+        let mixed = Hash::from(hashv(&[
+            hash(tick.as_ref()).as_ref(),
+            hash_signatures(&[signature]).as_ref(),
+        ]));
+        assert_eq!(next_hash(&tick, 2, &[signature]), mixed);
+
+        let mut entries = (0..10)
+            .scan(start, |prev, _| {
+                let hash = next_hash(prev, 3, &[]);
+                *prev = hash;
+                Some(DecodedEntry {
+                    num_hashes: 3,
+                    hash,
+                    signatures: vec![],
+                })
+            })
+            .collect::<Vec<_>>();
+        for threads in [1, 3, 16] {
+            assert_eq!(verify_poh(&start, &entries, NonZeroUsize::new(threads)), []);
+        }
+        let threads = NonZeroUsize::new(4);
+
+        entries[4].num_hashes = 2;
+        let computed = next_hash(&entries[3].hash, 2, &[]);
+        assert_eq!(
+            verify_poh(&start, &entries, threads),
+            [PohMismatch {
+                index: 4,
+                expected: entries[4].hash,
+                computed,
+            }]
+        );
+        assert_eq!(verify_poh(&start, &[], threads), []);
+    }
+}
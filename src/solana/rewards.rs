@@ -0,0 +1,118 @@
+use {
+    crate::{
+        node::{Nodes, Rewards},
+        solana::{DecodeError, meta::deserialize_exact, reassemble_decompress},
+    },
+    prost::Message,
+    solana_storage_proto::{StoredExtendedRewards, convert::generated},
+    solana_transaction_status::Reward,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedRewards {
+    pub slot: u64,
+    /// Empty if rewards data is empty
+    pub rewards: Vec<Reward>,
+    /// Only in protobuf encoded rewards with partitioned epoch rewards
+    pub num_partitions: Option<u64>,
+}
+
+impl DecodedRewards {
+    pub fn decode(rewards: &Rewards, nodes: &Nodes) -> Result<Self, DecodeError> {
+        let (rewards_list, num_partitions) = match reassemble_decompress(nodes, &rewards.data)? {
+            Some(buffer) => Self::decode_rewards(&buffer)?,
+            None => (vec![], None),
+        };
+        Ok(Self {
+            slot: rewards.slot,
+            rewards: rewards_list,
+            num_partitions,
+        })
+    }
+
+    /// Decode decompressed rewards
+    ///
+    /// Both formats are decoded: protobuf should be re-encoded to the same length and bincode
+    /// should consume all bytes, rewards decoded by both formats are ambiguous.
+    pub fn decode_rewards(bytes: &[u8]) -> Result<(Vec<Reward>, Option<u64>), DecodeError> {
+        // prost skips unknown fields, re-encoded message is shorter for garbage input
+        let protobuf = generated::Rewards::decode(bytes)
+            .ok()
+            .filter(|rewards| rewards.encoded_len() == bytes.len());
+        let bincode = deserialize_exact::<StoredExtendedRewards>(bytes).ok();
+        match (protobuf, bincode) {
+            (Some(rewards), None) => Ok(rewards.into()),
+            (None, Some(rewards)) => Ok((rewards.into_iter().map(Into::into).collect(), None)),
+            (Some(_), Some(_)) => Err(DecodeError::AmbiguousRewards),
+            (None, None) => Err(DecodeError::UnknownRewards),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::DecodedRewards,
+        crate::{
+            node::{DataFrame, Nodes, Rewards},
+            solana::DecodeError,
+        },
+        prost::Message,
+        solana_storage_proto::{StoredExtendedReward, convert::generated},
+        solana_transaction_status::{Reward, RewardType},
+    };
+
+    fn build_rewards(data: Vec<u8>) -> Rewards {
+        Rewards {
+            slot: 42,
+            data: DataFrame {
+                data,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let reward = Reward {
+            pubkey: "11111111111111111111111111111111".to_owned(),
+            lamports: -5,
+            post_balance: 100,
+            reward_type: Some(RewardType::Voting),
+            commission: Some(10),
+        };
+
+        let rewards = build_rewards(vec![]);
+        let decoded = DecodedRewards::decode(&rewards, &Nodes::default()).expect("valid rewards");
+        assert_eq!(decoded.slot, 42);
+        assert!(decoded.rewards.is_empty());
+
+        let protobuf = generated::Rewards {
+            rewards: vec![reward.clone().into()],
+            num_partitions: Some(generated::NumPartitions { num_partitions: 3 }),
+        }
+        .encode_to_vec();
+        let bincode = bincode::serialize(&vec![StoredExtendedReward::from(reward.clone())])
+            .expect("valid rewards");
+        for (data, num_partitions) in [(protobuf, Some(3)), (bincode, None)] {
+            let data = zstd::encode_all(data.as_slice(), 0).expect("valid zstd");
+            let rewards = build_rewards(data);
+            let decoded =
+                DecodedRewards::decode(&rewards, &Nodes::default()).expect("valid rewards");
+            assert_eq!(decoded.rewards, [reward.clone()]);
+            assert_eq!(decoded.num_partitions, num_partitions);
+        }
+
+        // trailing garbage is skipped by prost as unknown field and rejected by bincode
+        let mut garbage = generated::Rewards {
+            rewards: vec![reward.into()],
+            num_partitions: None,
+        }
+        .encode_to_vec();
+        garbage.extend_from_slice(&[0xf8, 0x01, 0x00]);
+        assert!(matches!(
+            DecodedRewards::decode_rewards(&garbage),
+            Err(DecodeError::UnknownRewards)
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+use {
+    crate::{
+        node::{Nodes, Transaction},
+        solana::{DecodeError, MetaDetection, MetaDetector, reassemble_decompress},
+        types::Signature,
+    },
+    solana_sdk::{
+        message::{
+            VersionedMessage,
+            v0::{LoadedAddresses, LoadedMessage},
+        },
+        pubkey::Pubkey,
+        transaction::VersionedTransaction,
+    },
+    solana_transaction_status::TransactionStatusMeta,
+    std::collections::HashSet,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountKeySource {
+    Static,
+    /// Loaded from address lookup table as writable
+    LoadedWritable,
+    /// Loaded from address lookup table as readonly
+    LoadedReadonly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub signer: bool,
+    /// Reserved account keys are not known, so only program ids are demoted to readonly
+    pub writable: bool,
+    pub source: AccountKeySource,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedTransaction {
+    pub signature: Signature,
+    pub slot: u64,
+    pub index: Option<u64>,
+    pub tx: VersionedTransaction,
+    /// `None` if metadata is empty
+    pub meta: Option<TransactionStatusMeta>,
+}
+
+impl DecodedTransaction {
+    pub fn decode(transaction: &Transaction, nodes: &Nodes) -> Result<Self, DecodeError> {
+        Self::decode_with_detector(transaction, nodes, &MetaDetector::default())
+    }
+
+    pub fn decode_with_detector(
+        transaction: &Transaction,
+        nodes: &Nodes,
+        detector: &MetaDetector,
+    ) -> Result<Self, DecodeError> {
+        let payload = transaction.payload(nodes)?;
+        Self::decode_payload(&payload, transaction, nodes, detector)
+    }
+
+    /// Decode with already reassembled transaction data, see [`Transaction::payload`]
+    pub fn decode_payload(
+        payload: &[u8],
+        transaction: &Transaction,
+        nodes: &Nodes,
+        detector: &MetaDetector,
+    ) -> Result<Self, DecodeError> {
+        let tx = bincode::deserialize::<VersionedTransaction>(payload)
+            .map_err(DecodeError::Transaction)?;
+        let signature = tx
+            .signatures
+            .first()
+            .copied()
+            .ok_or(DecodeError::MissedSignature)?
+            .into();
+
+        let meta = match reassemble_decompress(nodes, &transaction.metadata)? {
+            Some(buffer) => {
+                let account_keys = tx.message.static_account_keys().len();
+                match detector.detect(&buffer, transaction.slot, Some(account_keys)) {
+                    MetaDetection::Detected { meta, .. } => Some(*meta),
+                    MetaDetection::Ambiguous(candidates) => {
+                        return Err(DecodeError::AmbiguousMetadata(
+                            candidates
+                                .into_iter()
+                                .map(|(format, _meta)| format)
+                                .collect(),
+                        ));
+                    }
+                    MetaDetection::Unknown => return Err(DecodeError::UnknownMetadata),
+                }
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            signature,
+            slot: transaction.slot,
+            index: transaction.index,
+            tx,
+            meta,
+        })
+    }
+
+    /// Loaded addresses from metadata, empty if metadata is missed
+    pub fn loaded_addresses(&self) -> LoadedAddresses {
+        self.meta
+            .as_ref()
+            .map(|meta| meta.loaded_addresses.clone())
+            .unwrap_or_default()
+    }
+
+    /// Complete ordered account list: static keys, writable loaded addresses and readonly
+    /// loaded addresses
+    pub fn accounts(&self) -> Vec<TransactionAccount> {
+        let empty = HashSet::new();
+        match &self.tx.message {
+            VersionedMessage::Legacy(message) => message
+                .account_keys
+                .iter()
+                .enumerate()
+                .map(|(index, pubkey)| TransactionAccount {
+                    pubkey: *pubkey,
+                    signer: message.is_signer(index),
+                    writable: message.is_maybe_writable(index, None),
+                    source: AccountKeySource::Static,
+                })
+                .collect(),
+            VersionedMessage::V0(message) => {
+                let loaded_addresses = self.loaded_addresses();
+                let loaded = LoadedMessage::new_borrowed(message, &loaded_addresses, &empty);
+                let static_count = message.account_keys.len();
+                let writable_count = static_count + loaded_addresses.writable.len();
+                loaded
+                    .account_keys()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, pubkey)| TransactionAccount {
+                        pubkey: *pubkey,
+                        signer: loaded.is_signer(index),
+                        writable: loaded.is_writable(index),
+                        source: if index < static_count {
+                            AccountKeySource::Static
+                        } else if index < writable_count {
+                            AccountKeySource::LoadedWritable
+                        } else {
+                            AccountKeySource::LoadedReadonly
+                        },
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Complete ordered list of account keys, same order as in [`Self::accounts`]
+    pub fn account_keys(&self) -> Vec<Pubkey> {
+        let mut keys = self.tx.message.static_account_keys().to_vec();
+        if let Some(meta) = &self.meta {
+            keys.extend(&meta.loaded_addresses.writable);
+            keys.extend(&meta.loaded_addresses.readonly);
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{AccountKeySource, DecodedTransaction, TransactionAccount},
+        crate::{
+            node::{DataFrame, Nodes, Transaction},
+            solana::{DecodeError, StoredTransactionStatusMeta},
+        },
+        solana_sdk::{
+            hash::Hash,
+            instruction::CompiledInstruction,
+            message::{
+                Message, MessageHeader, VersionedMessage,
+                v0::{self, LoadedAddresses},
+            },
+            pubkey::Pubkey,
+            signature::Signature,
+            transaction::{TransactionError, VersionedTransaction},
+        },
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    fn build_transaction(data: Vec<u8>, metadata: Vec<u8>) -> Transaction {
+        Transaction {
+            data: DataFrame {
+                data,
+                ..Default::default()
+            },
+            metadata: DataFrame {
+                data: metadata,
+                ..Default::default()
+            },
+            slot: 42,
+            index: Some(3),
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::from([7; 64])],
+            message: VersionedMessage::Legacy(Message::new_with_blockhash(
+                &[],
+                Some(&Pubkey::new_from_array([1; 32])),
+                &Hash::default(),
+            )),
+        };
+        let payload = bincode::serialize(&tx).expect("valid tx");
+
+        let transaction = build_transaction(payload.clone(), vec![]);
+        let decoded =
+            DecodedTransaction::decode(&transaction, &Nodes::default()).expect("valid tx");
+        assert_eq!(decoded.signature, [7; 64].into());
+        assert_eq!(decoded.slot, 42);
+        assert_eq!(decoded.index, Some(3));
+        assert_eq!(decoded.tx, tx);
+        assert_eq!(decoded.meta, None);
+
+        let meta = bincode::serialize(&(
+            Err::<(), _>(TransactionError::AccountNotFound),
+            5000u64,
+            vec![10u64],
+            vec![5u64],
+        ))
+        .expect("valid meta");
+        let meta = zstd::encode_all(meta.as_slice(), 0).expect("valid zstd");
+        let transaction = build_transaction(payload, meta);
+        let decoded =
+            DecodedTransaction::decode(&transaction, &Nodes::default()).expect("valid tx");
+        assert_eq!(
+            decoded.meta,
+            Some(TransactionStatusMeta::from(StoredTransactionStatusMeta {
+                err: Err(TransactionError::AccountNotFound),
+                fee: 5000,
+                pre_balances: vec![10],
+                post_balances: vec![5],
+            }))
+        );
+
+        let transaction = build_transaction(vec![1, 2, 3], vec![]);
+        assert!(matches!(
+            DecodedTransaction::decode(&transaction, &Nodes::default()),
+            Err(DecodeError::Transaction(_))
+        ));
+    }
+
+    #[test]
+    fn test_accounts() {
+        let keys = [1, 2, 3, 4].map(|seed| Pubkey::new_from_array([seed; 32]));
+        let tx = DecodedTransaction {
+            signature: [1; 64].into(),
+            slot: 1,
+            index: None,
+            tx: VersionedTransaction {
+                signatures: vec![Signature::from([1; 64])],
+                message: VersionedMessage::V0(v0::Message {
+                    header: MessageHeader {
+                        num_required_signatures: 1,
+                        num_readonly_signed_accounts: 0,
+                        num_readonly_unsigned_accounts: 1,
+                    },
+                    account_keys: vec![keys[0], keys[1]],
+                    instructions: vec![CompiledInstruction::new_from_raw_parts(
+                        1,
+                        vec![],
+                        vec![0, 2, 3],
+                    )],
+                    ..Default::default()
+                }),
+            },
+            meta: Some(TransactionStatusMeta {
+                loaded_addresses: LoadedAddresses {
+                    writable: vec![keys[2]],
+                    readonly: vec![keys[3]],
+                },
+                ..Default::default()
+            }),
+        };
+
+        assert_eq!(tx.account_keys(), keys);
+        assert_eq!(
+            tx.accounts(),
+            [
+                (true, true, AccountKeySource::Static),
+                (false, false, AccountKeySource::Static),
+                (false, true, AccountKeySource::LoadedWritable),
+                (false, false, AccountKeySource::LoadedReadonly),
+            ]
+            .into_iter()
+            .zip(keys)
+            .map(|((signer, writable, source), pubkey)| TransactionAccount {
+                pubkey,
+                signer,
+                writable,
+                source,
+            })
+            .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use {
+    crate::{solana::parallel_filter_map, types::Signature},
+    cid::Cid,
+    solana_sdk::transaction::VersionedTransaction,
+    std::{
+        num::NonZeroUsize,
+        time::{Duration, Instant},
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureFailure {
+    /// CID of the Transaction node
+    pub cid: Cid,
+    /// Index of the signature in the transaction
+    pub index: usize,
+    pub signature: Signature,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockSignatures {
+    pub slot: u64,
+    pub transactions: usize,
+    pub signatures: usize,
+    pub failures: Vec<SignatureFailure>,
+    pub elapsed: Duration,
+}
+
+/// Verify every signature of every transaction, transactions are split between `threads`
+/// threads (by default the number of available CPUs)
+///
+/// Signature is invalid if it does not match the message or there is no signer key for it.
+pub fn verify_signatures(
+    transactions: &[(Cid, VersionedTransaction)],
+    threads: Option<NonZeroUsize>,
+) -> Vec<SignatureFailure> {
+    parallel_filter_map(transactions.len(), threads, |position| {
+        let (cid, tx) = &transactions[position];
+        let message = tx.message.serialize();
+        let keys = tx.message.static_account_keys();
+        let failures = tx
+            .signatures
+            .iter()
+            .enumerate()
+            .filter(|(index, signature)| {
+                !keys
+                    .get(*index)
+                    .is_some_and(|key| signature.verify(key.as_ref(), &message))
+            })
+            .map(|(index, signature)| SignatureFailure {
+                cid: *cid,
+                index,
+                signature: (*signature).into(),
+            })
+            .collect::<Vec<_>>();
+        (!failures.is_empty()).then_some(failures)
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Verifier of signatures over the transaction stream, keeps totals for throughput
+#[derive(Debug, Default, Clone)]
+pub struct SignatureVerifier {
+    threads: Option<NonZeroUsize>,
+    pub blocks: usize,
+    pub transactions: usize,
+    pub signatures: usize,
+    pub failures: usize,
+    pub elapsed: Duration,
+}
+
+impl SignatureVerifier {
+    /// Every block is verified with `threads` threads, see [`verify_signatures`]
+    pub fn new(threads: Option<NonZeroUsize>) -> Self {
+        Self {
+            threads,
+            ..Default::default()
+        }
+    }
+
+    pub fn push_block(
+        &mut self,
+        slot: u64,
+        transactions: &[(Cid, VersionedTransaction)],
+    ) -> BlockSignatures {
+        let ts = Instant::now();
+        let failures = verify_signatures(transactions, self.threads);
+        let elapsed = ts.elapsed();
+
+        let signatures = transactions
+            .iter()
+            .map(|(_cid, tx)| tx.signatures.len())
+            .sum();
+        self.blocks += 1;
+        self.transactions += transactions.len();
+        self.signatures += signatures;
+        self.failures += failures.len();
+        self.elapsed += elapsed;
+
+        BlockSignatures {
+            slot,
+            transactions: transactions.len(),
+            signatures,
+            failures,
+            elapsed,
+        }
+    }
+
+    /// Verified signatures per second
+    pub fn throughput(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            0.0 => 0.0,
+            secs => self.signatures as f64 / secs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{SignatureFailure, SignatureVerifier},
+        crate::util::tests::make_cid,
+        solana_sdk::{
+            hash::Hash,
+            message::{Message, VersionedMessage},
+            signature::{Keypair, Signature},
+            signer::Signer,
+            transaction::VersionedTransaction,
+        },
+        std::num::NonZeroUsize,
+    };
+
+    #[test]
+    fn test_verify() {
+        let keypair = Keypair::new();
+        let message = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[],
+            Some(&keypair.pubkey()),
+            &Hash::default(),
+        ));
+        let valid = VersionedTransaction::try_new(message.clone(), &[&keypair]).expect("signed");
+        let invalid = VersionedTransaction {
+            signatures: vec![Signature::from([1; 64])],
+            message,
+        };
+
+        let mut verifier = SignatureVerifier::new(NonZeroUsize::new(2));
+        let report = verifier.push_block(
+            5,
+            &[
+                (make_cid(1), valid.clone()),
+                (make_cid(2), invalid),
+                (make_cid(3), valid),
+            ],
+        );
+        assert_eq!(report.slot, 5);
+        assert_eq!(report.transactions, 3);
+        assert_eq!(report.signatures, 3);
+        assert_eq!(
+            report.failures,
+            [SignatureFailure {
+                cid: make_cid(2),
+                index: 0,
+                signature: [1; 64].into(),
+            }]
+        );
+        assert_eq!(verifier.blocks, 1);
+        assert_eq!(verifier.failures, 1);
+    }
+}
@@ -0,0 +1,406 @@
+use {
+    crate::node::{DataFrame, Node, NodeError, NodeReader, Nodes},
+    cid::Cid,
+    indexmap::IndexMap,
+    std::collections::HashSet,
+    thiserror::Error,
+    tokio::io::AsyncRead,
+};
+
+const DEFAULT_MAX_CARRIED_SIZE: usize = 256 << 20; // 256MiB
+/// Size of a referenced but not found frame, counted in `max_carried_size`
+const MISSED_FRAME_SIZE: usize = std::mem::size_of::<(Cid, Option<u64>)>();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Drop frames carried for the longest time
+    Oldest,
+    /// Drop the largest frames first
+    Largest,
+    /// Return an error on reaching the limit
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StreamingConfig {
+    /// Max size of data in carried DataFrames and of references to not found frames
+    pub max_carried_size: usize,
+    pub eviction: EvictionPolicy,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            max_carried_size: DEFAULT_MAX_CARRIED_SIZE,
+            eviction: EvictionPolicy::Oldest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnclaimedReason {
+    Evicted,
+    EndOfFile,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnclaimedFrame {
+    pub cid: Cid,
+    /// Slot of the block after which frame was carried
+    pub slot: Option<u64>,
+    pub reason: UnclaimedReason,
+}
+
+#[derive(Debug)]
+struct CarriedFrame {
+    frame: DataFrame,
+    slot: Option<u64>,
+}
+
+/// Reads blocks like [`Nodes::read_until_block`], but DataFrames not referenced by the block
+/// are kept and added to the next blocks which reference them.
+///
+/// Only frames emitted before the referencing block can be resolved, blocks are not held back.
+/// A frame emitted after the referencing block is reported with
+/// [`StreamingError::FrameAfterBlock`] while the reference is remembered; references are
+/// dropped oldest first when carried frames are not left to evict.
+#[derive(Debug)]
+pub struct StreamingReassembler<R> {
+    reader: NodeReader<R>,
+    config: StreamingConfig,
+    carried: IndexMap<Cid, CarriedFrame>,
+    /// Frames referenced by already processed blocks but not found, with the block slot
+    missed: IndexMap<Cid, Option<u64>>,
+    carried_size: usize,
+    unclaimed: Vec<UnclaimedFrame>,
+}
+
+impl<R: AsyncRead + Unpin> StreamingReassembler<R> {
+    pub fn new(reader: NodeReader<R>, config: StreamingConfig) -> Self {
+        Self {
+            reader,
+            config,
+            carried: IndexMap::new(),
+            missed: IndexMap::new(),
+            carried_size: 0,
+            unclaimed: vec![],
+        }
+    }
+
+    pub const fn carried_size(&self) -> usize {
+        self.carried_size
+    }
+
+    pub fn carried_count(&self) -> usize {
+        self.carried.len()
+    }
+
+    /// Read nodes until the next block, returns empty `Nodes` at the end of the file
+    pub async fn read_until_block(&mut self) -> Result<Nodes, StreamingError> {
+        let mut nodes = Nodes::read_until_block(&mut self.reader).await?;
+        self.process(&mut nodes)?;
+        Ok(nodes)
+    }
+
+    /// Frames evicted so far, or all unclaimed frames when the whole file is read
+    pub fn finish(mut self) -> Vec<UnclaimedFrame> {
+        self.unclaimed
+            .extend(self.carried.drain(..).map(|(cid, carried)| UnclaimedFrame {
+                cid,
+                slot: carried.slot,
+                reason: UnclaimedReason::EndOfFile,
+            }));
+        self.unclaimed
+    }
+
+    fn process(&mut self, nodes: &mut Nodes) -> Result<(), StreamingError> {
+        let slot = match nodes.nodes.last() {
+            Some((_cid, Node::Block(block))) => Some(block.slot),
+            _ => None,
+        };
+
+        // collect links to DataFrames and claim carried frames
+        let mut referenced = HashSet::new();
+        let mut claimed = IndexMap::new();
+        let mut queue = vec![];
+        for node in nodes.nodes.values() {
+            match node {
+                Node::Transaction(node) => {
+                    queue.extend(&node.data.next);
+                    queue.extend(&node.metadata.next);
+                }
+                Node::Rewards(node) => queue.extend(&node.data.next),
+                _ => {}
+            }
+        }
+        while let Some(cid) = queue.pop() {
+            if !referenced.insert(cid) {
+                continue;
+            }
+            if !nodes.nodes.contains_key(&cid) {
+                match self.carried.shift_remove(&cid) {
+                    Some(carried) => {
+                        self.carried_size -= carried.frame.data.len();
+                        claimed.insert(cid, Node::DataFrame(carried.frame));
+                    }
+                    None => {
+                        self.missed.insert(cid, slot);
+                    }
+                }
+            }
+            if let Some(Node::DataFrame(frame)) = nodes.nodes.get(&cid).or(claimed.get(&cid)) {
+                queue.extend(&frame.next);
+            }
+        }
+
+        // claimed frames go first to keep Block as the last node, not referenced frames are
+        // carried to the next blocks
+        let mut result = claimed;
+        result.reserve(nodes.nodes.len());
+        for (cid, node) in nodes.nodes.drain(..) {
+            match node {
+                Node::DataFrame(frame) if !referenced.contains(&cid) => {
+                    if let Some(block_slot) = self.missed.swap_remove(&cid) {
+                        return Err(StreamingError::FrameAfterBlock {
+                            cid,
+                            slot: block_slot,
+                        });
+                    }
+                    self.carried_size += frame.data.len();
+                    self.carried.insert(cid, CarriedFrame { frame, slot });
+                }
+                node => {
+                    result.insert(cid, node);
+                }
+            }
+        }
+        nodes.nodes = result;
+
+        self.evict()
+    }
+
+    fn size(&self) -> usize {
+        self.carried_size + self.missed.len() * MISSED_FRAME_SIZE
+    }
+
+    fn evict(&mut self) -> Result<(), StreamingError> {
+        while self.size() > self.config.max_carried_size {
+            if self.carried.is_empty() && self.config.eviction != EvictionPolicy::Fail {
+                let excess =
+                    (self.size() - self.config.max_carried_size).div_ceil(MISSED_FRAME_SIZE);
+                self.missed.drain(..excess.min(self.missed.len()));
+                break;
+            }
+            let index = match self.config.eviction {
+                EvictionPolicy::Oldest => 0,
+                EvictionPolicy::Largest => self
+                    .carried
+                    .values()
+                    .enumerate()
+                    .max_by_key(|(_index, carried)| carried.frame.data.len())
+                    .map(|(index, _carried)| index)
+                    .unwrap_or_default(),
+                EvictionPolicy::Fail => {
+                    return Err(StreamingError::MemoryLimit {
+                        size: self.size(),
+                        max: self.config.max_carried_size,
+                    });
+                }
+            };
+            let Some((cid, carried)) = self.carried.shift_remove_index(index) else {
+                break;
+            };
+            self.carried_size -= carried.frame.data.len();
+            self.unclaimed.push(UnclaimedFrame {
+                cid,
+                slot: carried.slot,
+                reason: UnclaimedReason::Evicted,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StreamingError {
+    #[error(transparent)]
+    Node(#[from] NodeError),
+    #[error("carried DataFrames size is too large: {size} (max {max})")]
+    MemoryLimit { size: usize, max: usize },
+    #[error("DataFrame {cid} is emitted after the referencing block (slot {slot:?})")]
+    FrameAfterBlock { cid: Cid, slot: Option<u64> },
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            EvictionPolicy, MISSED_FRAME_SIZE, StreamingConfig, StreamingError,
+            StreamingReassembler, UnclaimedFrame, UnclaimedReason,
+        },
+        crate::{
+            node::{Block, DataFrame, Node, NodeReader, Transaction},
+            util::tests::{build_nodes, make_cid},
+        },
+    };
+
+    fn frame(data: usize, next: Vec<u8>) -> Node {
+        Node::DataFrame(DataFrame {
+            data: vec![0; data],
+            next: next.into_iter().map(make_cid).collect(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_carry_frames() {
+        let mut reassembler =
+            StreamingReassembler::new(NodeReader::new(&[][..]), StreamingConfig::default());
+
+        let mut nodes = build_nodes([
+            (1, frame(10, vec![2])),
+            (2, frame(10, vec![])),
+            (3, frame(10, vec![])),
+            (
+                9,
+                Node::Block(Block {
+                    slot: 1,
+                    ..Default::default()
+                }),
+            ),
+        ]);
+        reassembler.process(&mut nodes).expect("no limit");
+        assert_eq!(nodes.nodes.len(), 1);
+        assert_eq!(reassembler.carried_count(), 3);
+        assert_eq!(reassembler.carried_size(), 30);
+
+        let mut nodes = build_nodes([
+            (
+                10,
+                Node::Transaction(Transaction {
+                    data: DataFrame {
+                        next: vec![make_cid(1)],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+            ),
+            (19, Node::Block(Block::default())),
+        ]);
+        reassembler.process(&mut nodes).expect("no limit");
+        assert_eq!(nodes.nodes.len(), 4);
+        assert!(matches!(nodes.nodes.last(), Some((_cid, Node::Block(_)))));
+        assert_eq!(reassembler.carried_count(), 1);
+
+        assert_eq!(
+            reassembler.finish(),
+            [UnclaimedFrame {
+                cid: make_cid(3),
+                slot: Some(1),
+                reason: UnclaimedReason::EndOfFile,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_frame_after_block() {
+        let mut reassembler =
+            StreamingReassembler::new(NodeReader::new(&[][..]), StreamingConfig::default());
+
+        let mut nodes = build_nodes([
+            (
+                1,
+                Node::Transaction(Transaction {
+                    data: DataFrame {
+                        next: vec![make_cid(2)],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+            ),
+            (
+                9,
+                Node::Block(Block {
+                    slot: 5,
+                    ..Default::default()
+                }),
+            ),
+        ]);
+        reassembler.process(&mut nodes).expect("no limit");
+
+        let mut nodes = build_nodes([(2, frame(10, vec![])), (19, Node::Block(Block::default()))]);
+        assert!(matches!(
+            reassembler.process(&mut nodes),
+            Err(StreamingError::FrameAfterBlock { cid, slot: Some(5) }) if cid == make_cid(2)
+        ));
+    }
+
+    #[test]
+    fn test_missed_limit() {
+        let mut reassembler = StreamingReassembler::new(
+            NodeReader::new(&[][..]),
+            StreamingConfig {
+                max_carried_size: 2 * MISSED_FRAME_SIZE,
+                eviction: EvictionPolicy::Oldest,
+            },
+        );
+
+        let transaction = |next: u8| {
+            Node::Transaction(Transaction {
+                data: DataFrame {
+                    next: vec![make_cid(next)],
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        };
+        let mut nodes = build_nodes([
+            (1, transaction(11)),
+            (2, transaction(12)),
+            (3, transaction(13)),
+            (9, Node::Block(Block::default())),
+        ]);
+        reassembler.process(&mut nodes).expect("no limit");
+        assert_eq!(reassembler.missed.len(), 2);
+        assert_eq!(reassembler.carried_count(), 0);
+    }
+
+    #[test]
+    fn test_eviction() {
+        for (eviction, evicted) in [
+            (EvictionPolicy::Oldest, Some(1)),
+            (EvictionPolicy::Largest, Some(2)),
+            (EvictionPolicy::Fail, None),
+        ] {
+            let mut reassembler = StreamingReassembler::new(
+                NodeReader::new(&[][..]),
+                StreamingConfig {
+                    max_carried_size: 25,
+                    eviction,
+                },
+            );
+            let mut nodes = build_nodes([
+                (1, frame(10, vec![])),
+                (2, frame(15, vec![])),
+                (3, frame(5, vec![])),
+            ]);
+            match evicted {
+                Some(cid) => {
+                    reassembler.process(&mut nodes).expect("evicted");
+                    assert_eq!(
+                        reassembler
+                            .unclaimed
+                            .iter()
+                            .map(|frame| frame.cid)
+                            .collect::<Vec<_>>(),
+                        [make_cid(cid)]
+                    );
+                }
+                None => assert!(matches!(
+                    reassembler.process(&mut nodes),
+                    Err(StreamingError::MemoryLimit { size: 30, max: 25 })
+                )),
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use {
+    std::{fmt, str::FromStr},
+    thiserror::Error,
+};
+
+pub const HASH_BYTES: usize = 32;
+pub const SIGNATURE_BYTES: usize = 64;
+
+// Longest base58 string for 32 bytes is 44 chars, for 64 bytes is 88 chars
+const MAX_BASE58_HASH_LEN: usize = 44;
+const MAX_BASE58_SIGNATURE_LEN: usize = 88;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ParseBytesError {
+    #[error("invalid length: {0} (expected {1})")]
+    InvalidLength(usize, usize),
+    #[error("base58 string is too long: {0} (max {1})")]
+    TooLong(usize, usize),
+    #[error("invalid base58: {0}")]
+    InvalidBase58(#[from] bs58::decode::Error),
+}
+
+/// Entry hash (PoH hash / blockhash)
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hash(pub [u8; HASH_BYTES]);
+
+impl Hash {
+    pub const fn new(bytes: [u8; HASH_BYTES]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; HASH_BYTES] {
+        &self.0
+    }
+
+    pub const fn to_bytes(self) -> [u8; HASH_BYTES] {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Hash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; HASH_BYTES]> for Hash {
+    fn from(value: [u8; HASH_BYTES]) -> Self {
+        Self(value)
+    }
+}
+
+impl TryFrom<&[u8]> for Hash {
+    type Error = ParseBytesError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        value
+            .try_into()
+            .map(Self)
+            .map_err(|_| ParseBytesError::InvalidLength(value.len(), HASH_BYTES))
+    }
+}
+
+impl fmt::Debug for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hash({self})")
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(&self.0).into_string())
+    }
+}
+
+impl FromStr for Hash {
+    type Err = ParseBytesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        decode_base58::<HASH_BYTES, MAX_BASE58_HASH_LEN>(s).map(Self)
+    }
+}
+
+/// Transaction signature
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Signature(pub [u8; SIGNATURE_BYTES]);
+
+impl Default for Signature {
+    fn default() -> Self {
+        Self([0; SIGNATURE_BYTES])
+    }
+}
+
+impl Signature {
+    pub const fn new(bytes: [u8; SIGNATURE_BYTES]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; SIGNATURE_BYTES] {
+        &self.0
+    }
+
+    pub const fn to_bytes(self) -> [u8; SIGNATURE_BYTES] {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; SIGNATURE_BYTES]> for Signature {
+    fn from(value: [u8; SIGNATURE_BYTES]) -> Self {
+        Self(value)
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = ParseBytesError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        value
+            .try_into()
+            .map(Self)
+            .map_err(|_| ParseBytesError::InvalidLength(value.len(), SIGNATURE_BYTES))
+    }
+}
+
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Signature({self})")
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(&self.0).into_string())
+    }
+}
+
+impl FromStr for Signature {
+    type Err = ParseBytesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        decode_base58::<SIGNATURE_BYTES, MAX_BASE58_SIGNATURE_LEN>(s).map(Self)
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<solana_sdk::hash::Hash> for Hash {
+    fn from(value: solana_sdk::hash::Hash) -> Self {
+        Self(value.to_bytes())
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<Hash> for solana_sdk::hash::Hash {
+    fn from(value: Hash) -> Self {
+        Self::new_from_array(value.0)
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<solana_sdk::signature::Signature> for Signature {
+    fn from(value: solana_sdk::signature::Signature) -> Self {
+        Self(value.into())
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<Signature> for solana_sdk::signature::Signature {
+    fn from(value: Signature) -> Self {
+        Self::from(value.0)
+    }
+}
+
+fn decode_base58<const N: usize, const MAX_LEN: usize>(
+    s: &str,
+) -> Result<[u8; N], ParseBytesError> {
+    if s.len() > MAX_LEN {
+        return Err(ParseBytesError::TooLong(s.len(), MAX_LEN));
+    }
+    let bytes = bs58::decode(s).into_vec()?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| ParseBytesError::InvalidLength(bytes.len(), N))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Hash, ParseBytesError, Signature},
+        crate::util::tests::decode_hex,
+    };
+
+    #[test]
+    fn test_base58() {
+        let hash = Hash::try_from(
+            decode_hex("3a43cd82e140873740fde924da4125ac30e2fec5eb92344dbb2bb4776973feec")
+                .as_slice(),
+        )
+        .expect("valid hash");
+        let encoded = hash.to_string();
+        assert_eq!(encoded.parse::<Hash>(), Ok(hash));
+
+        let signature =
+            Signature::try_from(decode_hex("01".repeat(64)).as_slice()).expect("valid signature");
+        let encoded = signature.to_string();
+        assert_eq!(encoded.parse::<Signature>(), Ok(signature));
+
+        assert_eq!(
+            Hash::try_from([0u8; 31].as_slice()),
+            Err(ParseBytesError::InvalidLength(31, 32))
+        );
+        assert!(signature.to_string().parse::<Hash>().is_err());
+        assert_eq!(
+            "1".repeat(45).parse::<Hash>(),
+            Err(ParseBytesError::TooLong(45, 44))
+        );
+    }
+}
@@ -16,7 +16,39 @@ pub async fn read_exact<R: AsyncRead + Unpin>(reader: &mut R, size: usize) -> io
 }
 
 pub mod cbor {
-    use {crate::node::NodeError, cid::Cid, serde_cbor::Value};
+    use {
+        crate::{node::NodeError, types::Hash},
+        cid::Cid,
+        serde_cbor::Value,
+    };
+
+    pub const MAJOR_UNSIGNED: u8 = 0;
+    pub const MAJOR_ARRAY: u8 = 4;
+
+    /// Read CBOR item header: major type and argument (value / length)
+    #[inline]
+    pub fn peek_header(data: &mut &[u8]) -> Option<(u8, u64)> {
+        let (&initial, rest) = data.split_first()?;
+        let major = initial >> 5;
+        let (value, size) = match initial & 0x1f {
+            value @ 0..=23 => (value as u64, 0),
+            24 => (*rest.first()? as u64, 1),
+            25 => (
+                u16::from_be_bytes(rest.get(..2)?.try_into().ok()?) as u64,
+                2,
+            ),
+            26 => (
+                u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as u64,
+                4,
+            ),
+            27 => (u64::from_be_bytes(rest.get(..8)?.try_into().ok()?), 8),
+            // indefinite length
+            31 if major == MAJOR_ARRAY => (u64::MAX, 0),
+            _ => return None,
+        };
+        *data = &rest[size..];
+        Some((major, value))
+    }
 
     #[inline]
     pub fn get_array(value: Value, path: &'static str) -> Result<Vec<Value>, NodeError> {
@@ -78,6 +110,15 @@ pub mod cbor {
         }
     }
 
+    #[inline]
+    pub fn get_hash(value: Value, path: &'static str) -> Result<Hash, NodeError> {
+        let bytes = get_bytes(value, path)?;
+        Hash::try_from(bytes.as_slice()).map_err(|_| NodeError::InvalidHashLength {
+            path,
+            len: bytes.len(),
+        })
+    }
+
     #[inline]
     pub fn get_cid(value: Value, path: &'static str) -> Result<Cid, NodeError> {
         Cid::try_from(&get_bytes(value, path)?[1..]).map_err(Into::into)
@@ -98,16 +139,43 @@ pub mod cbor {
 
 #[cfg(test)]
 pub mod tests {
-    use {cid::Cid, const_hex::decode};
+    use {
+        crate::{
+            node::{Node, NodeWithCid, Nodes},
+            types::Hash,
+        },
+        cid::Cid,
+        const_hex::decode,
+    };
 
     pub fn decode_hex<T: AsRef<[u8]>>(input: T) -> Vec<u8> {
         decode(input).unwrap()
     }
 
+    pub fn decode_hash<T: AsRef<[u8]>>(input: T) -> Hash {
+        Hash::try_from(decode_hex(input).as_slice()).unwrap()
+    }
+
     pub fn decode_cid(cid: &'static str) -> Cid {
         Cid::try_from(cid).unwrap()
     }
 
+    pub fn make_cid(seed: u8) -> Cid {
+        Cid::new_v1(0x71, multihash::Multihash::wrap(0x12, &[seed; 32]).unwrap())
+    }
+
+    /// Nodes with CIDs created by [`make_cid`] from seeds
+    pub fn build_nodes(nodes: impl IntoIterator<Item = (u8, Node)>) -> Nodes {
+        let mut result = Nodes::default();
+        for (cid, node) in nodes {
+            result.push(NodeWithCid {
+                cid: make_cid(cid),
+                node,
+            });
+        }
+        result
+    }
+
     pub fn decode_cids<const N: usize>(cids: [&'static str; N]) -> Vec<Cid> {
         cids.into_iter().map(decode_cid).collect()
     }
@@ -0,0 +1,11 @@
+pub use {
+    chain::{ChainIssue, ChainValidator},
+    epoch::{EpochIssue, EpochVerifier, verify_epoch},
+    index::{IndexIssue, validate_transaction_indices},
+    links::{FileLinkValidator, LinkIssue, validate_block_links, validate_file_links},
+};
+
+mod chain;
+mod epoch;
+mod index;
+mod links;
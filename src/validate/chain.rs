@@ -0,0 +1,223 @@
+use crate::node::Block;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainIssue {
+    /// Parent slot is not less than the block slot
+    InvalidParent { slot: u64, parent_slot: u64 },
+    /// Parent slot is before the previous block, block is on another fork
+    Fork {
+        slot: u64,
+        parent_slot: u64,
+        prev_slot: u64,
+    },
+    /// Parent slot is after the previous block, parent block is missed
+    Gap {
+        slot: u64,
+        parent_slot: u64,
+        prev_slot: u64,
+    },
+    /// Block height is not the previous block height plus one
+    BlockHeight {
+        slot: u64,
+        block_height: u64,
+        expected: u64,
+    },
+    /// Block time is before the previous block time (more than the tolerance), unknown (zero)
+    /// block times are not compared
+    BlockTime {
+        slot: u64,
+        blocktime: u64,
+        prev_blocktime: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PrevBlock {
+    slot: u64,
+    blocktime: u64,
+    block_height: Option<u64>,
+}
+
+/// Validator of the parent-slot chain, block height and block time, blocks should be pushed
+/// in the file order
+#[derive(Debug, Default, Clone)]
+pub struct ChainValidator {
+    blocktime_tolerance: u64,
+    prev: Option<PrevBlock>,
+}
+
+impl ChainValidator {
+    /// Block time is allowed to go backwards by `blocktime_tolerance` seconds
+    pub const fn new(blocktime_tolerance: u64) -> Self {
+        Self {
+            blocktime_tolerance,
+            prev: None,
+        }
+    }
+
+    pub fn push_block(&mut self, block: &Block) -> Vec<ChainIssue> {
+        let mut issues = vec![];
+        let slot = block.slot;
+        let parent_slot = block.meta.parent_slot;
+
+        // genesis block is the parent of itself
+        if parent_slot >= slot && slot != 0 {
+            issues.push(ChainIssue::InvalidParent { slot, parent_slot });
+        }
+
+        if let Some(prev) = self.prev {
+            if parent_slot < prev.slot {
+                issues.push(ChainIssue::Fork {
+                    slot,
+                    parent_slot,
+                    prev_slot: prev.slot,
+                });
+            } else if parent_slot > prev.slot {
+                issues.push(ChainIssue::Gap {
+                    slot,
+                    parent_slot,
+                    prev_slot: prev.slot,
+                });
+            }
+
+            if let (Some(block_height), Some(prev_block_height)) =
+                (block.meta.block_height, prev.block_height)
+            {
+                if prev_block_height.checked_add(1) != Some(block_height) {
+                    issues.push(ChainIssue::BlockHeight {
+                        slot,
+                        block_height,
+                        expected: prev_block_height.saturating_add(1),
+                    });
+                }
+            }
+
+            // zero block time is unknown
+            if prev.blocktime != 0
+                && block.meta.blocktime != 0
+                && prev.blocktime.saturating_sub(self.blocktime_tolerance) > block.meta.blocktime
+            {
+                issues.push(ChainIssue::BlockTime {
+                    slot,
+                    blocktime: block.meta.blocktime,
+                    prev_blocktime: prev.blocktime,
+                });
+            }
+        }
+
+        self.prev = Some(PrevBlock {
+            slot,
+            blocktime: block.meta.blocktime,
+            block_height: block.meta.block_height,
+        });
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{ChainIssue, ChainValidator},
+        crate::node::{Block, SlotMeta},
+    };
+
+    fn block(slot: u64, parent_slot: u64, blocktime: u64, block_height: Option<u64>) -> Block {
+        Block {
+            slot,
+            meta: SlotMeta {
+                parent_slot,
+                blocktime,
+                block_height,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_chain() {
+        let mut validator = ChainValidator::new(1);
+        for (block, issues) in [
+            (block(10, 9, 100, Some(5)), vec![]),
+            (block(12, 10, 100, Some(6)), vec![]),
+            (
+                block(13, 11, 99, Some(8)),
+                vec![
+                    ChainIssue::Fork {
+                        slot: 13,
+                        parent_slot: 11,
+                        prev_slot: 12,
+                    },
+                    ChainIssue::BlockHeight {
+                        slot: 13,
+                        block_height: 8,
+                        expected: 7,
+                    },
+                ],
+            ),
+            (
+                block(16, 15, 97, None),
+                vec![
+                    ChainIssue::Gap {
+                        slot: 16,
+                        parent_slot: 15,
+                        prev_slot: 13,
+                    },
+                    ChainIssue::BlockTime {
+                        slot: 16,
+                        blocktime: 97,
+                        prev_blocktime: 99,
+                    },
+                ],
+            ),
+            (
+                block(17, 17, 97, Some(10)),
+                vec![
+                    ChainIssue::InvalidParent {
+                        slot: 17,
+                        parent_slot: 17,
+                    },
+                    ChainIssue::Gap {
+                        slot: 17,
+                        parent_slot: 17,
+                        prev_slot: 16,
+                    },
+                ],
+            ),
+        ] {
+            assert_eq!(validator.push_block(&block), issues);
+        }
+    }
+
+    #[test]
+    fn test_unknown_blocktime() {
+        let mut validator = ChainValidator::new(0);
+        assert_eq!(validator.push_block(&block(1, 0, 100, None)), []);
+        assert_eq!(validator.push_block(&block(2, 1, 0, None)), []);
+        assert_eq!(validator.push_block(&block(3, 2, 50, None)), []);
+        assert_eq!(
+            validator.push_block(&block(4, 3, 49, None)),
+            [ChainIssue::BlockTime {
+                slot: 4,
+                blocktime: 49,
+                prev_blocktime: 50,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut validator = ChainValidator::new(u64::MAX);
+        assert_eq!(
+            validator.push_block(&block(1, 0, u64::MAX, Some(u64::MAX))),
+            []
+        );
+        assert_eq!(
+            validator.push_block(&block(2, 1, 0, Some(0))),
+            [ChainIssue::BlockHeight {
+                slot: 2,
+                block_height: 0,
+                expected: u64::MAX,
+            }]
+        );
+    }
+}
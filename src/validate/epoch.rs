@@ -0,0 +1,457 @@
+use {
+    crate::node::{
+        CarHeader, Epoch, Kind, KindFilter, Node, NodeError, NodeReader, Nodes, SLOTS_PER_EPOCH,
+        Subset,
+    },
+    cid::Cid,
+    std::collections::{HashMap, HashSet},
+    tokio::io::AsyncRead,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpochIssue {
+    /// Epoch node not found
+    MissedEpoch,
+    /// CAR header root is not the Epoch node
+    RootMismatch { roots: Vec<Cid>, epoch: Cid },
+    /// Epoch number is too large for slots
+    InvalidEpoch { epoch: u64 },
+    /// Subset linked from the Epoch not found
+    MissedSubset { subset: Cid },
+    /// Block linked from the Subset not found
+    MissedBlock { subset: Cid, block: Cid },
+    /// Subset `first` is greater than `last`
+    InvalidSubsetRange { subset: Cid, first: u64, last: u64 },
+    /// Subset range is not within the epoch
+    SubsetOutOfEpoch { subset: Cid, first: u64, last: u64 },
+    /// Subset range overlaps with the previous subset
+    SubsetOverlap {
+        subset: Cid,
+        first: u64,
+        prev_last: u64,
+    },
+    /// Block of the epoch not linked from subsets is before the first subset
+    EpochStart {
+        subset: Cid,
+        first: u64,
+        /// The earliest of such blocks
+        slot: u64,
+    },
+    /// Block of the epoch not linked from subsets is after the last subset, e.g. the subsets are
+    /// truncated
+    EpochEnd {
+        subset: Cid,
+        last: u64,
+        /// The latest of such blocks
+        slot: u64,
+    },
+    /// Block slot is not within the subset range
+    BlockOutOfSubset {
+        subset: Cid,
+        block: Cid,
+        slot: u64,
+        first: u64,
+        last: u64,
+    },
+    /// Block slot is not greater than the previous block slot in the subset
+    BlockOrder {
+        subset: Cid,
+        block: Cid,
+        slot: u64,
+        prev_slot: u64,
+    },
+    /// Block slot belongs to another epoch
+    BlockOutOfEpoch { block: Cid, slot: u64, epoch: u64 },
+}
+
+/// Verifier of Subset and Epoch nodes against blocks
+///
+/// Only slots of blocks and Subset / Epoch nodes are kept in memory.
+///
+/// Old Faithful sets subset ranges from slots of the first and the last blocks, so slots between
+/// subsets and leading or trailing slots of the epoch are skipped slots and are not reported.
+/// Only pushed blocks of the epoch outside of subset ranges are reported as
+/// [`EpochIssue::EpochStart`] / [`EpochIssue::EpochEnd`].
+#[derive(Debug, Default)]
+pub struct EpochVerifier {
+    blocks: HashMap<Cid, u64>,
+    subsets: HashMap<Cid, Subset>,
+    epoch: Option<(Cid, Epoch)>,
+}
+
+impl EpochVerifier {
+    pub fn push_nodes(&mut self, nodes: &Nodes) {
+        for (cid, node) in nodes.nodes.iter() {
+            self.push_node(*cid, node);
+        }
+    }
+
+    pub fn push_node(&mut self, cid: Cid, node: &Node) {
+        match node {
+            Node::Block(block) => {
+                self.blocks.insert(cid, block.slot);
+            }
+            Node::Subset(subset) => {
+                self.subsets.insert(cid, subset.clone());
+            }
+            Node::Epoch(epoch) => self.epoch = Some((cid, epoch.clone())),
+            _ => {}
+        }
+    }
+
+    pub fn finish(self, header: Option<&CarHeader>) -> Vec<EpochIssue> {
+        let Some((epoch_cid, epoch)) = self.epoch else {
+            return vec![EpochIssue::MissedEpoch];
+        };
+
+        let mut issues = vec![];
+        if let Some(header) = header {
+            if header.roots != [epoch_cid] {
+                issues.push(EpochIssue::RootMismatch {
+                    roots: header.roots.clone(),
+                    epoch: epoch_cid,
+                });
+            }
+        }
+
+        let Some((epoch_first, epoch_last)) = epoch.slot_range() else {
+            issues.push(EpochIssue::InvalidEpoch { epoch: epoch.epoch });
+            return issues;
+        };
+        let found = epoch
+            .subsets
+            .iter()
+            .filter_map(|cid| self.subsets.get(cid).map(|subset| (*cid, subset)))
+            .collect::<Vec<_>>();
+        if let (Some((first_cid, first)), Some((last_cid, last))) = (found.first(), found.last()) {
+            let linked = found
+                .iter()
+                .flat_map(|(_cid, subset)| subset.blocks.iter())
+                .collect::<HashSet<_>>();
+            let unlinked = self
+                .blocks
+                .iter()
+                .filter(|(cid, slot)| {
+                    !linked.contains(cid) && **slot / SLOTS_PER_EPOCH == epoch.epoch
+                })
+                .map(|(_cid, slot)| *slot);
+            let (mut before, mut after) = (None::<u64>, None::<u64>);
+            for slot in unlinked {
+                if slot < first.first {
+                    before = Some(before.map_or(slot, |before| before.min(slot)));
+                }
+                if slot > last.last {
+                    after = Some(after.map_or(slot, |after| after.max(slot)));
+                }
+            }
+            if let Some(slot) = before {
+                issues.push(EpochIssue::EpochStart {
+                    subset: *first_cid,
+                    first: first.first,
+                    slot,
+                });
+            }
+            if let Some(slot) = after {
+                issues.push(EpochIssue::EpochEnd {
+                    subset: *last_cid,
+                    last: last.last,
+                    slot,
+                });
+            }
+        }
+
+        let mut prev_last = None;
+        for subset_cid in epoch.subsets.iter() {
+            let Some(subset) = self.subsets.get(subset_cid) else {
+                issues.push(EpochIssue::MissedSubset {
+                    subset: *subset_cid,
+                });
+                continue;
+            };
+
+            if subset.first > subset.last {
+                issues.push(EpochIssue::InvalidSubsetRange {
+                    subset: *subset_cid,
+                    first: subset.first,
+                    last: subset.last,
+                });
+            }
+            if subset.first < epoch_first || subset.last > epoch_last {
+                issues.push(EpochIssue::SubsetOutOfEpoch {
+                    subset: *subset_cid,
+                    first: subset.first,
+                    last: subset.last,
+                });
+            }
+            if let Some(prev_last) = prev_last {
+                if subset.first <= prev_last {
+                    issues.push(EpochIssue::SubsetOverlap {
+                        subset: *subset_cid,
+                        first: subset.first,
+                        prev_last,
+                    });
+                }
+            }
+            prev_last = Some(subset.last);
+
+            let mut prev_slot = None;
+            for block_cid in subset.blocks.iter() {
+                let Some(&slot) = self.blocks.get(block_cid) else {
+                    issues.push(EpochIssue::MissedBlock {
+                        subset: *subset_cid,
+                        block: *block_cid,
+                    });
+                    continue;
+                };
+
+                if slot < subset.first || slot > subset.last {
+                    issues.push(EpochIssue::BlockOutOfSubset {
+                        subset: *subset_cid,
+                        block: *block_cid,
+                        slot,
+                        first: subset.first,
+                        last: subset.last,
+                    });
+                }
+                if let Some(prev_slot) = prev_slot {
+                    if slot <= prev_slot {
+                        issues.push(EpochIssue::BlockOrder {
+                            subset: *subset_cid,
+                            block: *block_cid,
+                            slot,
+                            prev_slot,
+                        });
+                    }
+                }
+                prev_slot = Some(slot);
+
+                if slot / SLOTS_PER_EPOCH != epoch.epoch {
+                    issues.push(EpochIssue::BlockOutOfEpoch {
+                        block: *block_cid,
+                        slot,
+                        epoch: epoch.epoch,
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+/// Verify Subset and Epoch nodes of the whole file, only Block / Subset / Epoch nodes are decoded
+pub async fn verify_epoch<R: AsyncRead + Unpin>(reader: R) -> Result<Vec<EpochIssue>, NodeError> {
+    let mut reader = NodeReader::new(reader).with_filter(KindFilter::from_iter([
+        Kind::Block,
+        Kind::Subset,
+        Kind::Epoch,
+    ]));
+    let header = CarHeader::try_from(reader.read_header().await?)?;
+
+    let mut verifier = EpochVerifier::default();
+    loop {
+        let nodes = Nodes::read_until_block(&mut reader).await?;
+        if nodes.nodes.is_empty() {
+            break;
+        }
+        verifier.push_nodes(&nodes);
+    }
+    Ok(verifier.finish(Some(&header)))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{EpochIssue, EpochVerifier},
+        crate::{
+            node::{Block, CarHeader, Epoch, Node, Subset},
+            util::tests::make_cid,
+        },
+    };
+
+    #[test]
+    fn test_verify() {
+        let mut verifier = EpochVerifier::default();
+        for (cid, node) in [
+            (
+                1,
+                Node::Block(Block {
+                    slot: 432_000,
+                    ..Default::default()
+                }),
+            ),
+            (
+                2,
+                Node::Block(Block {
+                    slot: 432_005,
+                    ..Default::default()
+                }),
+            ),
+            (
+                3,
+                Node::Block(Block {
+                    slot: 432_003,
+                    ..Default::default()
+                }),
+            ),
+            (
+                4,
+                Node::Block(Block {
+                    slot: 864_000,
+                    ..Default::default()
+                }),
+            ),
+            (
+                10,
+                Node::Subset(Subset {
+                    first: 432_000,
+                    last: 432_004,
+                    blocks: vec![make_cid(1), make_cid(2), make_cid(3)],
+                }),
+            ),
+            (
+                11,
+                Node::Subset(Subset {
+                    first: 432_006,
+                    last: 864_000,
+                    blocks: vec![make_cid(4), make_cid(5)],
+                }),
+            ),
+            (
+                20,
+                Node::Epoch(Epoch {
+                    epoch: 1,
+                    subsets: vec![make_cid(10), make_cid(11)],
+                }),
+            ),
+        ] {
+            verifier.push_node(make_cid(cid), &node);
+        }
+
+        let header = CarHeader {
+            roots: vec![make_cid(21)],
+            version: 1,
+        };
+        assert_eq!(
+            verifier.finish(Some(&header)),
+            [
+                EpochIssue::RootMismatch {
+                    roots: vec![make_cid(21)],
+                    epoch: make_cid(20),
+                },
+                EpochIssue::BlockOutOfSubset {
+                    subset: make_cid(10),
+                    block: make_cid(2),
+                    slot: 432_005,
+                    first: 432_000,
+                    last: 432_004,
+                },
+                EpochIssue::BlockOrder {
+                    subset: make_cid(10),
+                    block: make_cid(3),
+                    slot: 432_003,
+                    prev_slot: 432_005,
+                },
+                EpochIssue::SubsetOutOfEpoch {
+                    subset: make_cid(11),
+                    first: 432_006,
+                    last: 864_000,
+                },
+                EpochIssue::BlockOutOfEpoch {
+                    block: make_cid(4),
+                    slot: 864_000,
+                    epoch: 1,
+                },
+                EpochIssue::MissedBlock {
+                    subset: make_cid(11),
+                    block: make_cid(5),
+                },
+            ]
+        );
+        assert_eq!(
+            EpochVerifier::default().finish(None),
+            [EpochIssue::MissedEpoch]
+        );
+    }
+
+    #[test]
+    fn test_invalid_epoch() {
+        let mut verifier = EpochVerifier::default();
+        verifier.push_node(
+            make_cid(1),
+            &Node::Epoch(Epoch {
+                epoch: u64::MAX,
+                subsets: vec![],
+            }),
+        );
+        assert_eq!(
+            verifier.finish(None),
+            [EpochIssue::InvalidEpoch { epoch: u64::MAX }]
+        );
+    }
+
+    #[test]
+    fn test_epoch_boundaries() {
+        let build = |slots: &[(u8, u64)]| {
+            let mut verifier = EpochVerifier::default();
+            let blocks = slots.iter().map(|(cid, slot)| {
+                (
+                    *cid,
+                    Node::Block(Block {
+                        slot: *slot,
+                        ..Default::default()
+                    }),
+                )
+            });
+            // leading, trailing and between subsets slots are skipped
+            let nodes = [
+                (
+                    10,
+                    Node::Subset(Subset {
+                        first: 432_001,
+                        last: 432_010,
+                        blocks: vec![],
+                    }),
+                ),
+                (
+                    11,
+                    Node::Subset(Subset {
+                        first: 432_020,
+                        last: 500_000,
+                        blocks: vec![],
+                    }),
+                ),
+                (
+                    20,
+                    Node::Epoch(Epoch {
+                        epoch: 1,
+                        subsets: vec![make_cid(10), make_cid(11)],
+                    }),
+                ),
+            ];
+            for (cid, node) in blocks.chain(nodes) {
+                verifier.push_node(make_cid(cid), &node);
+            }
+            verifier.finish(None)
+        };
+
+        assert_eq!(build(&[]), []);
+        // blocks between subsets and of other epochs are not boundary issues
+        assert_eq!(build(&[(1, 432_015), (2, 900_000)]), []);
+        assert_eq!(
+            build(&[(1, 432_000), (2, 600_000), (3, 700_000)]),
+            [
+                EpochIssue::EpochStart {
+                    subset: make_cid(10),
+                    first: 432_001,
+                    slot: 432_000,
+                },
+                EpochIssue::EpochEnd {
+                    subset: make_cid(11),
+                    last: 500_000,
+                    slot: 700_000,
+                },
+            ]
+        );
+    }
+}
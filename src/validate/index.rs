@@ -0,0 +1,138 @@
+use {crate::bundle::BlockBundle, cid::Cid};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexIssue {
+    /// Indices not set, filled with the position in the slot; reported once per block, early
+    /// epochs have no indices at all
+    Missed { slot: u64, count: usize },
+    /// Index is not the position in the slot
+    Mismatch { cid: Cid, index: u64, position: u64 },
+    /// Transaction slot is not the block slot
+    SlotMismatch {
+        cid: Cid,
+        slot: u64,
+        block_slot: u64,
+    },
+}
+
+/// Validate `Transaction::index` and `Transaction::slot` against the position derived from
+/// `Block::entries` and `Entry::transactions`, missed indices are filled with the position
+pub fn validate_transaction_indices(bundle: &mut BlockBundle) -> Vec<IndexIssue> {
+    let block_slot = bundle.block.slot;
+    let mut issues = vec![];
+    let mut missed = 0;
+    let transactions = bundle
+        .entries
+        .iter_mut()
+        .flat_map(|entry| entry.transactions.iter_mut());
+    for (position, (cid, tx)) in transactions.enumerate() {
+        let position = position as u64;
+        match tx.index {
+            Some(index) if index != position => issues.push(IndexIssue::Mismatch {
+                cid: *cid,
+                index,
+                position,
+            }),
+            Some(_) => {}
+            None => {
+                tx.index = Some(position);
+                missed += 1;
+            }
+        }
+        if tx.slot != block_slot {
+            issues.push(IndexIssue::SlotMismatch {
+                cid: *cid,
+                slot: tx.slot,
+                block_slot,
+            });
+        }
+    }
+    if missed > 0 {
+        issues.push(IndexIssue::Missed {
+            slot: block_slot,
+            count: missed,
+        });
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{IndexIssue, validate_transaction_indices},
+        crate::{
+            bundle::BlockBundle,
+            node::{Block, Entry, Node, Transaction},
+            util::tests::{build_nodes, make_cid},
+        },
+    };
+
+    #[test]
+    fn test_validate() {
+        let nodes = build_nodes(
+            [
+                (1, (10, Some(0))),
+                (2, (10, None)),
+                (3, (11, Some(2))),
+                (4, (10, Some(2))),
+            ]
+            .map(|(cid, (slot, index))| {
+                (
+                    cid,
+                    Node::Transaction(Transaction {
+                        slot,
+                        index,
+                        ..Default::default()
+                    }),
+                )
+            })
+            .into_iter()
+            .chain([
+                (
+                    5,
+                    Node::Entry(Entry {
+                        transactions: vec![make_cid(1), make_cid(2)],
+                        ..Default::default()
+                    }),
+                ),
+                (
+                    6,
+                    Node::Entry(Entry {
+                        transactions: vec![make_cid(3), make_cid(4)],
+                        ..Default::default()
+                    }),
+                ),
+                (
+                    7,
+                    Node::Block(Block {
+                        slot: 10,
+                        entries: vec![make_cid(5), make_cid(6)],
+                        ..Default::default()
+                    }),
+                ),
+            ]),
+        );
+        let mut bundle = BlockBundle::try_from(nodes).expect("valid bundle");
+
+        assert_eq!(
+            validate_transaction_indices(&mut bundle),
+            [
+                IndexIssue::SlotMismatch {
+                    cid: make_cid(3),
+                    slot: 11,
+                    block_slot: 10,
+                },
+                IndexIssue::Mismatch {
+                    cid: make_cid(4),
+                    index: 2,
+                    position: 3,
+                },
+                IndexIssue::Missed { slot: 10, count: 1 },
+            ]
+        );
+        assert_eq!(
+            bundle.transaction(1).map(|(_cid, tx)| tx.index),
+            Some(Some(1))
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use {
+    crate::node::{Kind, Node, NodeError, NodeReader, Nodes},
+    cid::Cid,
+    indexmap::IndexMap,
+    tokio::io::AsyncRead,
+};
+
+// Identity multihash is used for dummy links (for example, `Block::rewards` without rewards)
+const MULTIHASH_IDENTITY: u64 = 0x00;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkIssue {
+    /// Linked node not found
+    Missed { from: Cid, to: Cid, expected: Kind },
+    /// Linked node has unexpected kind
+    InvalidKind {
+        from: Cid,
+        to: Cid,
+        kind: Kind,
+        expected: Kind,
+    },
+    /// Node not referenced by any other node
+    Orphan { cid: Cid, kind: Kind },
+    /// Node referenced more than once
+    Duplicate { cid: Cid, kind: Kind, count: usize },
+}
+
+#[derive(Debug, Default)]
+struct LinkGraph {
+    // kind and number of references
+    nodes: IndexMap<Cid, (Kind, usize)>,
+    // from, to, expected kind
+    links: Vec<(Cid, Cid, Kind)>,
+}
+
+impl LinkGraph {
+    fn add_node(&mut self, cid: Cid, kind: Kind) {
+        self.nodes.entry(cid).or_insert((kind, 0));
+    }
+
+    fn add_links<'a>(&mut self, from: Cid, to: impl IntoIterator<Item = &'a Cid>, expected: Kind) {
+        for cid in to {
+            if cid.hash().code() != MULTIHASH_IDENTITY {
+                self.links.push((from, *cid, expected));
+            }
+        }
+    }
+
+    fn add_block_links(&mut self, cid: Cid, node: &Node) {
+        match node {
+            Node::Transaction(node) => {
+                self.add_links(cid, &node.data.next, Kind::DataFrame);
+                self.add_links(cid, &node.metadata.next, Kind::DataFrame);
+            }
+            Node::Entry(node) => self.add_links(cid, &node.transactions, Kind::Transaction),
+            Node::Block(node) => {
+                self.add_links(cid, &node.entries, Kind::Entry);
+                self.add_links(cid, [&node.rewards], Kind::Rewards);
+            }
+            Node::Rewards(node) => self.add_links(cid, &node.data.next, Kind::DataFrame),
+            Node::DataFrame(node) => self.add_links(cid, &node.next, Kind::DataFrame),
+            // links to nodes from previous blocks
+            Node::Subset(_) | Node::Epoch(_) => {}
+        }
+    }
+
+    fn finish(mut self, is_root: impl Fn(Kind) -> bool) -> Vec<LinkIssue> {
+        let mut issues = vec![];
+        for (from, to, expected) in self.links {
+            match self.nodes.get_mut(&to) {
+                Some((kind, count)) => {
+                    *count += 1;
+                    if *kind != expected {
+                        issues.push(LinkIssue::InvalidKind {
+                            from,
+                            to,
+                            kind: *kind,
+                            expected,
+                        });
+                    }
+                }
+                None => issues.push(LinkIssue::Missed { from, to, expected }),
+            }
+        }
+        for (cid, (kind, count)) in self.nodes {
+            match count {
+                0 if !is_root(kind) => issues.push(LinkIssue::Orphan { cid, kind }),
+                0 | 1 => {}
+                count => issues.push(LinkIssue::Duplicate { cid, kind, count }),
+            }
+        }
+        issues
+    }
+}
+
+/// Validate links between nodes of one block (as returned by [`Nodes::read_until_block`])
+///
+/// Links from `Subset` and `Epoch` point to previous blocks and validated by [`FileLinkValidator`].
+pub fn validate_block_links(nodes: &Nodes) -> Vec<LinkIssue> {
+    let mut graph = LinkGraph::default();
+    for (cid, node) in nodes.nodes.iter() {
+        graph.add_node(*cid, node.kind());
+        graph.add_block_links(*cid, node);
+    }
+    graph.finish(|kind| matches!(kind, Kind::Block | Kind::Subset | Kind::Epoch))
+}
+
+/// Links validator for the whole file
+///
+/// Only `Block`, `Subset` and `Epoch` nodes are kept in memory between blocks.
+#[derive(Debug, Default)]
+pub struct FileLinkValidator {
+    graph: LinkGraph,
+}
+
+impl FileLinkValidator {
+    /// Validate block links and remember links between blocks, subsets and epoch
+    pub fn push_nodes(&mut self, nodes: &Nodes) -> Vec<LinkIssue> {
+        for (cid, node) in nodes.nodes.iter() {
+            match node {
+                Node::Block(_) => self.graph.add_node(*cid, Kind::Block),
+                Node::Subset(node) => {
+                    self.graph.add_node(*cid, Kind::Subset);
+                    self.graph.add_links(*cid, &node.blocks, Kind::Block);
+                }
+                Node::Epoch(node) => {
+                    self.graph.add_node(*cid, Kind::Epoch);
+                    self.graph.add_links(*cid, &node.subsets, Kind::Subset);
+                }
+                _ => {}
+            }
+        }
+        validate_block_links(nodes)
+    }
+
+    pub fn finish(self) -> Vec<LinkIssue> {
+        self.graph.finish(|kind| kind == Kind::Epoch)
+    }
+}
+
+pub async fn validate_file_links<R: AsyncRead + Unpin>(
+    reader: &mut NodeReader<R>,
+) -> Result<Vec<LinkIssue>, NodeError> {
+    let mut validator = FileLinkValidator::default();
+    let mut issues = vec![];
+    loop {
+        let nodes = Nodes::read_until_block(reader).await?;
+        if nodes.nodes.is_empty() {
+            break;
+        }
+        issues.extend(validator.push_nodes(&nodes));
+    }
+    issues.extend(validator.finish());
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{FileLinkValidator, LinkIssue, validate_block_links},
+        crate::{
+            node::{Block, DataFrame, Entry, Epoch, Kind, Node, Subset, Transaction},
+            util::tests::{build_nodes, decode_cid, make_cid},
+        },
+    };
+
+    #[test]
+    fn test_block_links() {
+        let nodes = build_nodes([
+            (1, Node::DataFrame(DataFrame::default())),
+            (2, Node::DataFrame(DataFrame::default())),
+            (
+                10,
+                Node::Transaction(Transaction {
+                    metadata: DataFrame {
+                        next: vec![make_cid(1), make_cid(3)],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+            ),
+            (
+                20,
+                Node::Entry(Entry {
+                    transactions: vec![make_cid(10), make_cid(1)],
+                    ..Default::default()
+                }),
+            ),
+            (
+                21,
+                Node::Entry(Entry {
+                    transactions: vec![make_cid(10)],
+                    ..Default::default()
+                }),
+            ),
+            (
+                30,
+                Node::Block(Block {
+                    entries: vec![make_cid(20), make_cid(21)],
+                    rewards: decode_cid("bafkqaaa"),
+                    ..Default::default()
+                }),
+            ),
+        ]);
+
+        assert_eq!(
+            validate_block_links(&nodes),
+            [
+                LinkIssue::Missed {
+                    from: make_cid(10),
+                    to: make_cid(3),
+                    expected: Kind::DataFrame,
+                },
+                LinkIssue::InvalidKind {
+                    from: make_cid(20),
+                    to: make_cid(1),
+                    kind: Kind::DataFrame,
+                    expected: Kind::Transaction,
+                },
+                LinkIssue::Duplicate {
+                    cid: make_cid(1),
+                    kind: Kind::DataFrame,
+                    count: 2,
+                },
+                LinkIssue::Orphan {
+                    cid: make_cid(2),
+                    kind: Kind::DataFrame,
+                },
+                LinkIssue::Duplicate {
+                    cid: make_cid(10),
+                    kind: Kind::Transaction,
+                    count: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_links() {
+        let mut validator = FileLinkValidator::default();
+        for nodes in [
+            build_nodes([(30, Node::Block(Block::default()))]),
+            build_nodes([(31, Node::Block(Block::default()))]),
+            build_nodes([
+                (
+                    40,
+                    Node::Subset(Subset {
+                        blocks: vec![make_cid(30)],
+                        ..Default::default()
+                    }),
+                ),
+                (
+                    50,
+                    Node::Epoch(Epoch {
+                        subsets: vec![make_cid(40), make_cid(41)],
+                        ..Default::default()
+                    }),
+                ),
+            ]),
+        ] {
+            assert_eq!(validator.push_nodes(&nodes), []);
+        }
+        assert_eq!(
+            validator.finish(),
+            [
+                LinkIssue::Missed {
+                    from: make_cid(50),
+                    to: make_cid(41),
+                    expected: Kind::Subset,
+                },
+                LinkIssue::Orphan {
+                    cid: make_cid(31),
+                    kind: Kind::Block,
+                },
+            ]
+        );
+    }
+}
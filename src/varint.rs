@@ -1,6 +1,6 @@
 // Based on https://github.com/tokio-rs/prost/blob/master/prost/src/encoding/varint.rs
 use {
-    bytes::Buf,
+    bytes::{Buf, BufMut},
     std::io,
     thiserror::Error,
     tokio::io::{AsyncRead, AsyncReadExt},
@@ -179,3 +179,25 @@ fn decode_varint_slow(buf: &mut impl Buf) -> Result<u64, VarIntError> {
 
     Err(VarIntError::Invalid)
 }
+
+/// Encodes an integer value into LEB128 variable length format, and writes it to the buffer.
+#[inline]
+pub fn encode_varint(mut value: u64, buf: &mut impl BufMut) {
+    for _ in 0..MAX_VARINT_LEN_64 {
+        if value < 0x80 {
+            buf.put_u8(value as u8);
+            break;
+        } else {
+            buf.put_u8(((value & 0x7F) | 0x80) as u8);
+            value >>= 7;
+        }
+    }
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format.
+#[inline]
+pub const fn encoded_len_varint(value: u64) -> usize {
+    // Based on [VarintSize64][1].
+    // [1]: https://github.com/google/protobuf/blob/3.3.x/src/google/protobuf/io/coded_stream.h#L1301-L1309
+    ((((value | 1).leading_zeros() ^ 63) * 9 + 73) / 64) as usize
+}
@@ -0,0 +1,270 @@
+use {
+    crate::node::{Block, Entry, Epoch, Kind, Node, Nodes, Subset, Transaction},
+    cid::Cid,
+    indexmap::IndexMap,
+    std::{collections::HashMap, slice},
+    thiserror::Error,
+};
+
+/// Random access to decoded nodes by CID
+pub trait NodeStore {
+    fn get_node(&self, cid: &Cid) -> Option<&Node>;
+}
+
+impl NodeStore for Nodes {
+    fn get_node(&self, cid: &Cid) -> Option<&Node> {
+        self.nodes.get(cid)
+    }
+}
+
+impl NodeStore for IndexMap<Cid, Node> {
+    fn get_node(&self, cid: &Cid) -> Option<&Node> {
+        self.get(cid)
+    }
+}
+
+impl NodeStore for HashMap<Cid, Node> {
+    fn get_node(&self, cid: &Cid) -> Option<&Node> {
+        self.get(cid)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkNode<'a> {
+    Epoch(&'a Epoch),
+    Subset(&'a Subset),
+    Block(&'a Block),
+    Entry(&'a Entry),
+    Transaction(&'a Transaction),
+}
+
+impl WalkNode<'_> {
+    pub const fn kind(&self) -> Kind {
+        match self {
+            Self::Epoch(_) => Kind::Epoch,
+            Self::Subset(_) => Kind::Subset,
+            Self::Block(_) => Kind::Block,
+            Self::Entry(_) => Kind::Entry,
+            Self::Transaction(_) => Kind::Transaction,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalkItem<'a> {
+    pub cid: Cid,
+    pub node: WalkNode<'a>,
+}
+
+/// Depth-first walker over the DAG: Epoch → Subset → Block → Entry → Transaction
+///
+/// Nodes are yielded in pre-order, children in the order of links in the parent node.
+#[derive(Debug)]
+pub struct EpochWalker<'a, S> {
+    store: &'a S,
+    root: Option<Cid>,
+    stack: Vec<(Kind, slice::Iter<'a, Cid>)>,
+}
+
+impl<'a, S: NodeStore> EpochWalker<'a, S> {
+    pub const fn new(store: &'a S, root: Cid) -> Self {
+        Self {
+            store,
+            root: Some(root),
+            stack: vec![],
+        }
+    }
+
+    pub fn blocks(self) -> impl Iterator<Item = Result<(Cid, &'a Block), WalkError>> {
+        self.filter_map(|item| match item {
+            Ok(WalkItem {
+                cid,
+                node: WalkNode::Block(block),
+            }) => Some(Ok((cid, block))),
+            Ok(_) => None,
+            Err(error) => Some(Err(error)),
+        })
+    }
+
+    pub fn transactions(self) -> impl Iterator<Item = Result<(Cid, &'a Transaction), WalkError>> {
+        self.filter_map(|item| match item {
+            Ok(WalkItem {
+                cid,
+                node: WalkNode::Transaction(tx),
+            }) => Some(Ok((cid, tx))),
+            Ok(_) => None,
+            Err(error) => Some(Err(error)),
+        })
+    }
+
+    fn get(&self, cid: Cid, expected: Kind) -> Result<WalkItem<'a>, WalkError> {
+        let node = self.store.get_node(&cid).ok_or(WalkError::MissedCid(cid))?;
+        let node = match (node, expected) {
+            (Node::Epoch(node), Kind::Epoch) => WalkNode::Epoch(node),
+            (Node::Subset(node), Kind::Subset) => WalkNode::Subset(node),
+            (Node::Block(node), Kind::Block) => WalkNode::Block(node),
+            (Node::Entry(node), Kind::Entry) => WalkNode::Entry(node),
+            (Node::Transaction(node), Kind::Transaction) => WalkNode::Transaction(node),
+            _ => {
+                return Err(WalkError::InvalidNode {
+                    cid,
+                    kind: node.kind(),
+                    expected,
+                });
+            }
+        };
+        Ok(WalkItem { cid, node })
+    }
+}
+
+impl<'a, S: NodeStore> Iterator for EpochWalker<'a, S> {
+    type Item = Result<WalkItem<'a>, WalkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = if let Some(root) = self.root.take() {
+            self.get(root, Kind::Epoch)
+        } else {
+            loop {
+                let (kind, cids) = self.stack.last_mut()?;
+                match cids.next() {
+                    Some(cid) => {
+                        let (cid, kind) = (*cid, *kind);
+                        break self.get(cid, kind);
+                    }
+                    None => {
+                        self.stack.pop();
+                    }
+                }
+            }
+        };
+
+        match item {
+            Ok(item) => {
+                match item.node {
+                    WalkNode::Epoch(node) => self.stack.push((Kind::Subset, node.subsets.iter())),
+                    WalkNode::Subset(node) => self.stack.push((Kind::Block, node.blocks.iter())),
+                    WalkNode::Block(node) => self.stack.push((Kind::Entry, node.entries.iter())),
+                    WalkNode::Entry(node) => self
+                        .stack
+                        .push((Kind::Transaction, node.transactions.iter())),
+                    WalkNode::Transaction(_) => {}
+                }
+                Some(Ok(item))
+            }
+            Err(error) => {
+                // stop walking on the first broken link
+                self.stack.clear();
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WalkError {
+    #[error("missed cid: {0}")]
+    MissedCid(Cid),
+    #[error("invalid node kind for {cid}: {kind:?} (expected {expected:?})")]
+    InvalidNode {
+        cid: Cid,
+        kind: Kind,
+        expected: Kind,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{EpochWalker, WalkError, WalkNode},
+        crate::{
+            node::{Block, Entry, Epoch, Kind, Node, Nodes, Subset, Transaction},
+            util::tests::{build_nodes, make_cid},
+        },
+    };
+
+    fn build_epoch() -> Nodes {
+        build_nodes([
+            (10, Node::Transaction(Transaction::default())),
+            (11, Node::Transaction(Transaction::default())),
+            (
+                20,
+                Node::Entry(Entry {
+                    transactions: vec![make_cid(10), make_cid(11)],
+                    ..Default::default()
+                }),
+            ),
+            (
+                21,
+                Node::Entry(Entry {
+                    transactions: vec![],
+                    ..Default::default()
+                }),
+            ),
+            (
+                30,
+                Node::Block(Block {
+                    slot: 1,
+                    entries: vec![make_cid(20), make_cid(21)],
+                    ..Default::default()
+                }),
+            ),
+            (
+                40,
+                Node::Subset(Subset {
+                    first: 1,
+                    last: 1,
+                    blocks: vec![make_cid(30)],
+                }),
+            ),
+            (
+                50,
+                Node::Epoch(Epoch {
+                    epoch: 0,
+                    subsets: vec![make_cid(40)],
+                }),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_walk() {
+        let mut nodes = build_epoch();
+
+        let order = EpochWalker::new(&nodes, make_cid(50))
+            .map(|item| item.map(|item| item.cid))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("valid dag");
+        assert_eq!(order, [50, 40, 30, 20, 10, 11, 21].map(make_cid));
+
+        let blocks = EpochWalker::new(&nodes, make_cid(50))
+            .blocks()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("valid dag");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].1.slot, 1);
+
+        let txs = EpochWalker::new(&nodes, make_cid(50))
+            .transactions()
+            .count();
+        assert_eq!(txs, 2);
+
+        nodes.nodes.shift_remove(&make_cid(11));
+        let items = EpochWalker::new(&nodes, make_cid(50)).collect::<Vec<_>>();
+        assert_eq!(items.len(), 6);
+        assert_eq!(items[5], Err(WalkError::MissedCid(make_cid(11))));
+
+        let items = EpochWalker::new(&nodes, make_cid(40)).collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            [Err(WalkError::InvalidNode {
+                cid: make_cid(40),
+                kind: Kind::Subset,
+                expected: Kind::Epoch,
+            })]
+        );
+        assert!(matches!(
+            EpochWalker::new(&build_epoch(), make_cid(50)).next(),
+            Some(Ok(item)) if matches!(item.node, WalkNode::Epoch(_))
+        ));
+    }
+}